@@ -0,0 +1,371 @@
+use crate::graphics::{BitDepth, ImageOptions};
+
+use super::CliError;
+
+/* Where to write the image and how to encode it */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Output {
+    pub path: String,
+    pub image: ImageOptions,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderArgs {
+    pub scene: String,
+    pub output: Output,
+    /* Overrides of the scene camera's size */
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub samples: usize,
+    pub threads: usize,
+    pub max_depth: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Help,
+    Render(RenderArgs),
+    Projectile { output: Output },
+    Clock { output: Output },
+    Silhouette { output: Output },
+}
+
+/* Options that are switched on by their presence and take no value */
+const FLAGS: &[&str] = &["binary"];
+
+/*
+ * Options come as `--name value` or `--name=value` and flags as `--name`,
+ * anything not starting with a dash is a positional argument.
+ */
+struct Options {
+    options: Vec<(String, String)>,
+    positional: Vec<String>,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, CliError> {
+        let mut options = vec![];
+        let mut positional = vec![];
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if !arg.starts_with('-') || arg == "-" {
+                positional.push(arg.clone());
+                continue;
+            }
+            if arg == "-h" || arg == "--help" {
+                options.push(("help".to_string(), String::new()));
+                continue;
+            }
+            let (name, value) = match arg.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+            let name = match name {
+                "-o" => "output".to_string(),
+                _ => match name.strip_prefix("--") {
+                    Some(long) if !long.is_empty() => long.to_string(),
+                    _ => return Err(CliError::Usage(format!("unknown option '{}'", name))),
+                },
+            };
+            let value = match value {
+                Some(value) => value,
+                None if FLAGS.contains(&name.as_str()) => String::new(),
+                None => iter
+                    .next()
+                    .ok_or_else(|| CliError::Usage(format!("{} needs a value", arg)))?
+                    .clone(),
+            };
+            options.push((name, value));
+        }
+        Ok(Self {
+            options,
+            positional,
+        })
+    }
+
+    fn has_help(&self) -> bool {
+        self.options.iter().any(|(n, _)| n == "help")
+    }
+
+    /* Fails on options the command does not know about */
+    fn check(&self, allowed: &[&str]) -> Result<(), CliError> {
+        match self
+            .options
+            .iter()
+            .find(|(n, _)| !allowed.contains(&n.as_str()))
+        {
+            Some((name, _)) => Err(CliError::Usage(format!("unknown option '--{}'", name))),
+            None => Ok(()),
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn number(&self, name: &str, min: usize) -> Result<Option<usize>, CliError> {
+        match self.get(name) {
+            Some(value) => match value.parse() {
+                Ok(n) if n >= min => Ok(Some(n)),
+                _ => Err(CliError::Usage(format!(
+                    "--{} expects a {} number, got '{}'",
+                    name,
+                    if min > 0 { "positive" } else { "non-negative" },
+                    value
+                ))),
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn count(&self, name: &str) -> Result<Option<usize>, CliError> {
+        self.number(name, 1)
+    }
+
+    /* Like `count` but zero is allowed */
+    fn amount(&self, name: &str) -> Result<Option<usize>, CliError> {
+        self.number(name, 0)
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    fn output(&self) -> Result<Output, CliError> {
+        let depth = match self.get("bit-depth") {
+            None | Some("8") => BitDepth::Eight,
+            Some("16") => BitDepth::Sixteen,
+            Some(other) => {
+                return Err(CliError::Usage(format!(
+                    "--bit-depth expects 8 or 16, got '{}'",
+                    other
+                )))
+            }
+        };
+        Ok(Output {
+            path: self.get("output").unwrap_or("output.ppm").to_string(),
+            image: ImageOptions {
+                depth,
+                binary: self.flag("binary"),
+            },
+        })
+    }
+}
+
+pub fn parse(args: &[String]) -> Result<Command, CliError> {
+    let Some((command, rest)) = args.split_first() else {
+        return Err(CliError::Usage("missing command".to_string()));
+    };
+    let options = Options::parse(rest)?;
+    if command == "help" || command == "-h" || command == "--help" || options.has_help() {
+        return Ok(Command::Help);
+    }
+
+    let demo = |options: &Options| -> Result<Output, CliError> {
+        options.check(&["output", "bit-depth", "binary"])?;
+        if let Some(extra) = options.positional.first() {
+            return Err(CliError::Usage(format!("unexpected argument '{}'", extra)));
+        }
+        options.output()
+    };
+
+    match command.as_str() {
+        "render" => {
+            options.check(&[
+                "output",
+                "width",
+                "height",
+                "samples",
+                "threads",
+                "max-depth",
+                "bit-depth",
+                "binary",
+            ])?;
+            let scene = match options.positional.as_slice() {
+                [scene] => scene.clone(),
+                [] => return Err(CliError::Usage("missing scene file".to_string())),
+                [_, extra, ..] => {
+                    return Err(CliError::Usage(format!("unexpected argument '{}'", extra)))
+                }
+            };
+            let threads = match options.count("threads")? {
+                Some(n) => n,
+                None => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            };
+            Ok(Command::Render(RenderArgs {
+                scene,
+                output: options.output()?,
+                width: options.count("width")?,
+                height: options.count("height")?,
+                samples: options.count("samples")?.unwrap_or(1),
+                threads,
+                max_depth: options.amount("max-depth")?,
+            }))
+        }
+        "projectile" => Ok(Command::Projectile {
+            output: demo(&options)?,
+        }),
+        "clock" => Ok(Command::Clock {
+            output: demo(&options)?,
+        }),
+        "silhouette" => Ok(Command::Silhouette {
+            output: demo(&options)?,
+        }),
+        _ => Err(CliError::Usage(format!("unknown command '{}'", command))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cli::CliError;
+    use crate::graphics::{BitDepth, ImageOptions};
+
+    use super::{parse, Command, Output, RenderArgs};
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    fn usage_error(line: &str) -> String {
+        match parse(&args(line)) {
+            Err(CliError::Usage(message)) => message,
+            other => panic!("expected a usage error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn render_with_all_options() {
+        let command = parse(&args(
+            "render scene.yaml -o out.png --width 640 --height=480 --samples 3 --threads 2 --max-depth 8 --bit-depth 16",
+        ))
+        .unwrap();
+        assert_eq!(
+            command,
+            Command::Render(RenderArgs {
+                scene: "scene.yaml".to_string(),
+                output: Output {
+                    path: "out.png".to_string(),
+                    image: ImageOptions {
+                        depth: BitDepth::Sixteen,
+                        binary: false,
+                    },
+                },
+                width: Some(640),
+                height: Some(480),
+                samples: 3,
+                threads: 2,
+                max_depth: Some(8),
+            })
+        );
+    }
+
+    #[test]
+    fn max_depth_may_be_zero() {
+        let Command::Render(render) = parse(&args("render scene.yaml --max-depth 0")).unwrap()
+        else {
+            panic!("expected a render command");
+        };
+        assert_eq!(render.max_depth, Some(0));
+    }
+
+    #[test]
+    fn render_defaults() {
+        let Command::Render(render) = parse(&args("render scene.yaml")).unwrap() else {
+            panic!("expected a render command");
+        };
+        assert_eq!(render.output.path, "output.ppm");
+        assert_eq!(render.output.image, ImageOptions::default());
+        assert_eq!(render.width, None);
+        assert_eq!(render.samples, 1);
+        assert!(render.threads >= 1);
+        assert_eq!(render.max_depth, None);
+    }
+
+    fn output(path: &str, depth: BitDepth, binary: bool) -> Output {
+        Output {
+            path: path.to_string(),
+            image: ImageOptions { depth, binary },
+        }
+    }
+
+    #[test]
+    fn demos() {
+        assert_eq!(
+            parse(&args("projectile")).unwrap(),
+            Command::Projectile {
+                output: output("output.ppm", BitDepth::Eight, false)
+            }
+        );
+        assert_eq!(
+            parse(&args("clock --output clock.ppm --bit-depth=16")).unwrap(),
+            Command::Clock {
+                output: output("clock.ppm", BitDepth::Sixteen, false)
+            }
+        );
+        assert_eq!(
+            parse(&args("silhouette -o s.ppm")).unwrap(),
+            Command::Silhouette {
+                output: output("s.ppm", BitDepth::Eight, false)
+            }
+        );
+    }
+
+    #[test]
+    fn binary_flag_takes_no_value() {
+        let Command::Render(render) = parse(&args("render --binary scene.yaml")).unwrap() else {
+            panic!("expected a render command");
+        };
+        assert_eq!(render.scene, "scene.yaml");
+        assert!(render.output.image.binary);
+        assert_eq!(
+            parse(&args("clock --binary")).unwrap(),
+            Command::Clock {
+                output: output("output.ppm", BitDepth::Eight, true)
+            }
+        );
+    }
+
+    #[test]
+    fn help() {
+        assert_eq!(parse(&args("help")).unwrap(), Command::Help);
+        assert_eq!(parse(&args("--help")).unwrap(), Command::Help);
+        assert_eq!(parse(&args("render -h")).unwrap(), Command::Help);
+    }
+
+    #[test]
+    fn usage_errors() {
+        assert_eq!(usage_error(""), "missing command");
+        assert_eq!(usage_error("draw"), "unknown command 'draw'");
+        assert_eq!(usage_error("render"), "missing scene file");
+        assert_eq!(
+            usage_error("render a.yaml b.yaml"),
+            "unexpected argument 'b.yaml'"
+        );
+        assert_eq!(
+            usage_error("render a.yaml --width"),
+            "--width needs a value"
+        );
+        assert_eq!(
+            usage_error("render a.yaml --width wide"),
+            "--width expects a positive number, got 'wide'"
+        );
+        assert_eq!(
+            usage_error("render a.yaml --samples 0"),
+            "--samples expects a positive number, got '0'"
+        );
+        assert_eq!(
+            usage_error("render a.yaml --max-depth -1"),
+            "--max-depth expects a non-negative number, got '-1'"
+        );
+        assert_eq!(
+            usage_error("render a.yaml --bit-depth 12"),
+            "--bit-depth expects 8 or 16, got '12'"
+        );
+        assert_eq!(usage_error("clock --width 5"), "unknown option '--width'");
+        assert_eq!(usage_error("clock -x 5"), "unknown option '-x'");
+    }
+}
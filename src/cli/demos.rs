@@ -0,0 +1,120 @@
+use std::f64::consts::PI;
+
+use crate::graphics::Canvas;
+use crate::math::{Matrix, Ray, Tuple};
+use crate::shapes::{Shape, Sphere};
+
+/* Colors a pixel given in world units, ignoring points off the canvas */
+fn plot(canvas: &mut Canvas, x: f64, y: f64, color: &Tuple) {
+    let (x, y) = (x.round(), y.round());
+    if x >= 0.0 && y >= 0.0 && (x as usize) < canvas.width && (y as usize) < canvas.height {
+        canvas.set_pixel(x as usize, y as usize, color);
+    }
+}
+
+/*
+ * The trajectory of a projectile fired into the wind and pulled down by
+ * gravity, drawn with the origin at the bottom left.
+ */
+pub fn projectile() -> Canvas {
+    let mut canvas = Canvas::new(900, 550);
+    let mut position = Tuple::point(0.0, 1.0, 0.0);
+    let mut velocity = Tuple::vector(1.0, 1.8, 0.0).normal() * 11.25;
+    let gravity = Tuple::vector(0.0, -0.1, 0.0);
+    let wind = Tuple::vector(-0.01, 0.0, 0.0);
+
+    let color = Tuple::color(1.0, 1.0, 1.0);
+    while position.y() > 0.0 {
+        let y = canvas.height as f64 - position.y();
+        plot(&mut canvas, position.x(), y, &color);
+        position = position + velocity;
+        velocity = velocity + gravity + wind;
+    }
+    canvas
+}
+
+/* The twelve hour marks of a clock face, placed by rotating a point */
+pub fn clock() -> Canvas {
+    let mut canvas = Canvas::new(200, 200);
+    let radius = canvas.width as f64 * 3.0 / 8.0;
+    let center = canvas.width as f64 / 2.0;
+    let twelve = Tuple::point(0.0, 0.0, 1.0);
+
+    let color = Tuple::color(1.0, 1.0, 1.0);
+    for hour in 0..12 {
+        let p = Matrix::rotation_y(hour as f64 * PI / 6.0) * twelve;
+        plot(
+            &mut canvas,
+            center + p.x() * radius,
+            center - p.z() * radius,
+            &color,
+        );
+    }
+    canvas
+}
+
+/* The shadow a unit sphere casts on a wall behind it */
+pub fn silhouette() -> Canvas {
+    let canvas_pixels = 100;
+    let wall_z = 10.0;
+    let wall_size = 7.0;
+    let pixel_size = wall_size / canvas_pixels as f64;
+    let half = wall_size / 2.0;
+
+    let mut canvas = Canvas::new(canvas_pixels, canvas_pixels);
+    let ray_origin = Tuple::point(0.0, 0.0, -5.0);
+    let shape = Sphere::new();
+
+    let color = Tuple::color(1.0, 0.0, 0.0);
+    for y in 0..canvas_pixels {
+        let world_y = half - pixel_size * y as f64;
+        for x in 0..canvas_pixels {
+            let world_x = -half + pixel_size * x as f64;
+            let position = Tuple::point(world_x, world_y, wall_z);
+            let r = Ray::new(ray_origin, (position - ray_origin).normal());
+            if shape.intersect(&r).hit().is_some() {
+                canvas.set_pixel(x, y, &color);
+            }
+        }
+    }
+    canvas
+}
+
+#[cfg(test)]
+mod test {
+    use crate::graphics::Canvas;
+    use crate::math::Tuple;
+
+    use super::{clock, projectile, silhouette};
+
+    fn lit_pixels(canvas: &Canvas) -> usize {
+        canvas
+            .matrix
+            .iter()
+            .flatten()
+            .filter(|p| **p != Tuple::color(0.0, 0.0, 0.0))
+            .count()
+    }
+
+    #[test]
+    fn projectile_lands_on_canvas() {
+        let c = projectile();
+        assert!(lit_pixels(&c) > 100);
+        assert_eq!(c.get_pixel(0, 549), &Tuple::color(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn clock_has_twelve_marks() {
+        let c = clock();
+        assert_eq!(lit_pixels(&c), 12);
+        assert_eq!(c.get_pixel(100, 25), &Tuple::color(1.0, 1.0, 1.0));
+        assert_eq!(c.get_pixel(175, 100), &Tuple::color(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn silhouette_is_centered() {
+        let c = silhouette();
+        assert_eq!(c.get_pixel(50, 50), &Tuple::color(1.0, 0.0, 0.0));
+        assert_eq!(c.get_pixel(0, 0), &Tuple::color(0.0, 0.0, 0.0));
+    }
+}
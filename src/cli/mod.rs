@@ -0,0 +1,149 @@
+mod args;
+mod demos;
+
+use std::process::ExitCode;
+use std::{error, fmt, io};
+
+use crate::graphics::Canvas;
+use crate::parsers::{Scene, SceneError};
+use crate::scene::Camera;
+
+use self::args::{Command, Output, RenderArgs};
+
+pub const USAGE: &str = "\
+Usage: raytracer-rs <command> [options]
+
+Commands:
+  render <scene.yaml>   Render a YAML scene
+  projectile            Plot the path of a projectile
+  clock                 Plot the hour marks of a clock
+  silhouette            Draw the silhouette of a sphere
+  help                  Show this message
+
+Options:
+  -o, --output <file>   Image to write, .png or .ppm (default output.ppm)
+  --bit-depth <8|16>    Bits per channel of the image (default 8)
+  --binary              Write PPM images as binary (P6) rather than text (P3)
+
+Render options:
+  --width <pixels>      Override the width of the scene camera
+  --height <pixels>     Override the height of the scene camera
+  --samples <n>         Cast n by n rays per pixel (default 1)
+  --threads <n>         Threads to render with (default: all cores)
+  --max-depth <n>       How many times rays may bounce (default 5)
+";
+
+#[derive(Debug)]
+pub enum CliError {
+    /* The command line itself was wrong */
+    Usage(String),
+    Scene(String, SceneError),
+    Write(String, io::Error),
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            CliError::Usage(_) => ExitCode::from(2),
+            _ => ExitCode::FAILURE,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}", message),
+            CliError::Scene(path, e) => write!(f, "failed to load {}: {}", path, e),
+            CliError::Write(path, e) => write!(f, "failed to write {}: {}", path, e),
+        }
+    }
+}
+
+impl error::Error for CliError {}
+
+pub fn run(args: &[String]) -> Result<(), CliError> {
+    match args::parse(args)? {
+        Command::Help => {
+            print!("{}", USAGE);
+            Ok(())
+        }
+        Command::Render(render_args) => render(&render_args),
+        Command::Projectile { output } => write(&demos::projectile(), &output),
+        Command::Clock { output } => write(&demos::clock(), &output),
+        Command::Silhouette { output } => write(&demos::silhouette(), &output),
+    }
+}
+
+fn render(args: &RenderArgs) -> Result<(), CliError> {
+    let Scene { mut world, camera } =
+        Scene::load(&args.scene).map_err(|e| CliError::Scene(args.scene.clone(), e))?;
+    if let Some(max_depth) = args.max_depth {
+        world.max_depth = max_depth;
+    }
+    let camera = resize(camera, args.width, args.height);
+
+    println!(
+        "Rendering {}x{} with {} thread(s)...",
+        camera.hsize, camera.vsize, args.threads
+    );
+    let canvas = camera.render_with(&world, args.samples, args.threads);
+    write(&canvas, &args.output)
+}
+
+/*
+ * Applies size overrides to the camera. When only one side is given the
+ * other follows so the aspect ratio is kept.
+ */
+fn resize(camera: Camera, width: Option<usize>, height: Option<usize>) -> Camera {
+    let aspect = camera.hsize as f64 / camera.vsize as f64;
+    let (hsize, vsize) = match (width, height) {
+        (None, None) => return camera,
+        (Some(w), Some(h)) => (w, h),
+        (Some(w), None) => (w, ((w as f64 / aspect).round() as usize).max(1)),
+        (None, Some(h)) => (((h as f64 * aspect).round() as usize).max(1), h),
+    };
+    let mut resized = Camera::new(hsize, vsize, camera.field_of_view);
+    resized.set_transform(camera.transform().clone());
+    resized
+}
+
+fn write(canvas: &Canvas, output: &Output) -> Result<(), CliError> {
+    canvas
+        .write_to_file_with(&output.path, &output.image)
+        .map_err(|e| CliError::Write(output.path.clone(), e))
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use crate::math::Matrix;
+    use crate::scene::Camera;
+
+    use super::{resize, run, CliError};
+
+    #[test]
+    fn resizing_keeps_aspect_ratio() {
+        let mut c = Camera::new(200, 100, PI / 3.0);
+        c.set_transform(Matrix::translation(1.0, 2.0, 3.0));
+        let r = resize(c.clone(), Some(50), None);
+        assert_eq!((r.hsize, r.vsize), (50, 25));
+        assert_eq!(r.field_of_view, PI / 3.0);
+        assert_eq!(r.transform(), c.transform());
+        let r = resize(c.clone(), None, Some(300));
+        assert_eq!((r.hsize, r.vsize), (600, 300));
+        let r = resize(c.clone(), Some(10), Some(10));
+        assert_eq!((r.hsize, r.vsize), (10, 10));
+    }
+
+    #[test]
+    fn missing_scene_is_reported() {
+        let args = vec!["render".to_string(), "no/such/scene.yaml".to_string()];
+        let e = run(&args).unwrap_err();
+        assert!(matches!(e, CliError::Scene(_, _)));
+        assert!(e
+            .to_string()
+            .starts_with("failed to load no/such/scene.yaml: "));
+    }
+}
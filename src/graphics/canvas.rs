@@ -1,11 +1,55 @@
 use std::{
-    fmt,
     fs::File,
-    io::{stdout, Write},
+    io::{self, stdout, BufWriter, Write},
+    path::Path,
 };
 
 use crate::math::Tuple;
 
+use super::png::write_png;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BitDepth {
+    #[default]
+    Eight,
+    Sixteen,
+}
+
+impl BitDepth {
+    pub fn bits(&self) -> u8 {
+        match self {
+            BitDepth::Eight => 8,
+            BitDepth::Sixteen => 16,
+        }
+    }
+
+    pub fn max_value(&self) -> u16 {
+        match self {
+            BitDepth::Eight => u8::MAX as u16,
+            BitDepth::Sixteen => u16::MAX,
+        }
+    }
+
+    /* Converts a color channel to an integer sample, clamping it to 0..1 */
+    pub fn quantize(&self, channel: f64) -> u16 {
+        (channel.clamp(0.0, 1.0) * self.max_value() as f64).round() as u16
+    }
+}
+
+/*
+ * How an image is encoded. The format itself follows the file extension,
+ * these only tune it.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ImageOptions {
+    pub depth: BitDepth,
+    /* Write PPM images as binary (P6) rather than plain text (P3) */
+    pub binary: bool,
+}
+
+/* Plain PPM readers may not accept lines longer than this */
+const PPM_LINE_LENGTH: usize = 70;
+
 pub struct Canvas {
     pub width: usize,
     pub height: usize,
@@ -31,34 +75,101 @@ impl Canvas {
         &self.matrix[y][x]
     }
 
-    pub fn write_to_file(&self, path: &str) -> std::io::Result<()> {
+    pub fn write_to_file(&self, path: &str) -> io::Result<()> {
+        self.write_to_file_with(path, &ImageOptions::default())
+    }
+
+    /* Writes the canvas as PNG or PPM depending on the file extension */
+    pub fn write_to_file_with(&self, path: &str, options: &ImageOptions) -> io::Result<()> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        let png = match extension.as_deref() {
+            Some("png") => true,
+            Some("ppm") => false,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "unsupported image format, use .png or .ppm",
+                ))
+            }
+        };
+
         print!("Writing to file... ");
         stdout().flush()?;
-        let mut file = File::create(path)?;
-        write!(file, "{}", self)?;
+        let mut file = BufWriter::new(File::create(path)?);
+        if png {
+            self.write_png(&mut file, options.depth)?;
+        } else if options.binary {
+            self.write_ppm(&mut file, options.depth)?;
+        } else {
+            self.write_plain_ppm(&mut file, options.depth)?;
+        }
+        file.flush()?;
         println!("Done");
         Ok(())
     }
-}
 
-impl fmt::Display for Canvas {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "P3")?;
-        writeln!(f, "{} {}", self.width, self.height)?;
-        writeln!(f, "255")?;
-        for row in &self.matrix {
-            for (i, p) in row.iter().enumerate() {
-                let r = (255.0 * p.red().clamp(0.0, 1.0)).clamp(0.0, 255.0).round() as u8;
-                let g = (255.0 * p.green().clamp(0.0, 1.0))
-                    .clamp(0.0, 255.0)
-                    .round() as u8;
-                let b = (255.0 * p.blue().clamp(0.0, 1.0)).clamp(0.0, 255.0).round() as u8;
-                write!(f, "{} {} {}", r, g, b)?;
-                if i < self.width - 1 {
-                    write!(f, " ")?;
+    pub fn write_png<W: Write>(&self, out: &mut W, depth: BitDepth) -> io::Result<()> {
+        write_png(out, self, depth)
+    }
+
+    fn samples(&self, depth: BitDepth) -> impl Iterator<Item = u16> + '_ {
+        self.matrix
+            .iter()
+            .flatten()
+            .flat_map(|p| [p.red(), p.green(), p.blue()])
+            .map(move |c| depth.quantize(c))
+    }
+
+    /* Binary PPM (P6), samples wider than a byte are stored big endian */
+    pub fn write_ppm<W: Write>(&self, out: &mut W, depth: BitDepth) -> io::Result<()> {
+        write!(
+            out,
+            "P6\n{} {}\n{}\n",
+            self.width,
+            self.height,
+            depth.max_value()
+        )?;
+        for sample in self.samples(depth) {
+            match depth {
+                BitDepth::Eight => out.write_all(&[sample as u8])?,
+                BitDepth::Sixteen => out.write_all(&sample.to_be_bytes())?,
+            }
+        }
+        Ok(())
+    }
+
+    /*
+     * Plain text PPM (P3). Every row starts on a new line and lines are
+     * wrapped so none is longer than 70 characters.
+     */
+    pub fn write_plain_ppm<W: Write>(&self, out: &mut W, depth: BitDepth) -> io::Result<()> {
+        write!(
+            out,
+            "P3\n{} {}\n{}\n",
+            self.width,
+            self.height,
+            depth.max_value()
+        )?;
+        let mut samples = self.samples(depth);
+        for _ in 0..self.height {
+            let mut line_length = 0;
+            for sample in samples.by_ref().take(self.width * 3) {
+                let sample = sample.to_string();
+                if line_length == 0 {
+                    line_length = sample.len();
+                } else if line_length + 1 + sample.len() > PPM_LINE_LENGTH {
+                    out.write_all(b"\n")?;
+                    line_length = sample.len();
+                } else {
+                    out.write_all(b" ")?;
+                    line_length += 1 + sample.len();
                 }
+                out.write_all(sample.as_bytes())?;
             }
-            writeln!(f)?;
+            out.write_all(b"\n")?;
         }
         Ok(())
     }
@@ -68,7 +179,7 @@ impl fmt::Display for Canvas {
 mod test {
     use crate::math::Tuple;
 
-    use super::Canvas;
+    use super::{BitDepth, Canvas, ImageOptions};
 
     #[test]
     fn canvas() {
@@ -85,10 +196,16 @@ mod test {
         assert_eq!(c.get_pixel(2, 3), &r);
     }
 
+    fn plain_ppm(c: &Canvas) -> String {
+        let mut out = vec![];
+        c.write_plain_ppm(&mut out, BitDepth::Eight).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
     #[test]
     fn to_canvas() {
         let c = Canvas::new(5, 3);
-        let output = format!("{}", c);
+        let output = plain_ppm(&c);
         let lines: Vec<&str> = output.split('\n').collect();
         assert_eq!(lines[0], "P3");
         assert_eq!(lines[1], "5 3");
@@ -101,7 +218,7 @@ mod test {
         c.set_pixel(0, 0, &Tuple::color(1.5, 0.0, 0.0));
         c.set_pixel(2, 1, &Tuple::color(0.0, 0.5, 0.0));
         c.set_pixel(4, 2, &Tuple::color(-0.5, 0.0, 1.0));
-        let output = format!("{}", c);
+        let output = plain_ppm(&c);
         let lines: Vec<&str> = output.split('\n').collect();
         assert_eq!(lines[0], "P3");
         assert_eq!(lines[1], "5 3");
@@ -114,7 +231,101 @@ mod test {
     #[test]
     fn ends_with_newline() {
         let c = Canvas::new(5, 3);
-        let output: String = format!("{}", c);
+        let output = plain_ppm(&c);
         assert_eq!(output.chars().last(), Some('\n'));
     }
+
+    #[test]
+    fn splitting_long_lines() {
+        let mut c = Canvas::new(10, 2);
+        for y in 0..2 {
+            for x in 0..10 {
+                c.set_pixel(x, y, &Tuple::color(1.0, 0.8, 0.6));
+            }
+        }
+        let output = plain_ppm(&c);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[3],
+            "255 204 153 255 204 153 255 204 153 255 204 153 255 204 153 255 204"
+        );
+        assert_eq!(
+            lines[4],
+            "153 255 204 153 255 204 153 255 204 153 255 204 153"
+        );
+        assert_eq!(lines[5], lines[3]);
+        assert_eq!(lines[6], lines[4]);
+        assert_eq!(lines.len(), 7);
+        assert!(lines.iter().all(|l| l.len() <= 70));
+    }
+
+    #[test]
+    fn plain_sixteen_bit() {
+        let mut c = Canvas::new(1, 1);
+        c.set_pixel(0, 0, &Tuple::color(1.0, 0.5, 0.0));
+        let mut out = vec![];
+        c.write_plain_ppm(&mut out, BitDepth::Sixteen).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "P3\n1 1\n65535\n65535 32768 0\n"
+        );
+    }
+
+    #[test]
+    fn binary_ppm() {
+        let mut c = Canvas::new(2, 1);
+        c.set_pixel(0, 0, &Tuple::color(1.5, 0.5, 0.0));
+        c.set_pixel(1, 0, &Tuple::color(0.0, 0.0, 1.0));
+        let mut out = vec![];
+        c.write_ppm(&mut out, BitDepth::Eight).unwrap();
+        let mut expected = b"P6\n2 1\n255\n".to_vec();
+        expected.extend([255, 128, 0, 0, 0, 255]);
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn binary_sixteen_bit_ppm() {
+        let mut c = Canvas::new(1, 1);
+        c.set_pixel(0, 0, &Tuple::color(1.0, 0.5, 0.0));
+        let mut out = vec![];
+        c.write_ppm(&mut out, BitDepth::Sixteen).unwrap();
+        let mut expected = b"P6\n1 1\n65535\n".to_vec();
+        expected.extend([0xff, 0xff, 0x80, 0x00, 0x00, 0x00]);
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn format_follows_extension() {
+        let c = Canvas::new(2, 2);
+        let path = std::env::temp_dir().join("canvas_format_follows_extension.PNG");
+        let path = path.to_str().unwrap();
+        c.write_to_file(path).unwrap();
+        let written = std::fs::read(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(written[1..4], *b"PNG");
+    }
+
+    #[test]
+    fn ppm_is_plain_unless_binary_is_asked_for() {
+        let c = Canvas::new(2, 2);
+        let path = std::env::temp_dir().join("canvas_ppm_is_plain.ppm");
+        let path = path.to_str().unwrap();
+        c.write_to_file(path).unwrap();
+        assert_eq!(std::fs::read(path).unwrap()[..2], *b"P3");
+        let options = ImageOptions {
+            binary: true,
+            ..Default::default()
+        };
+        c.write_to_file_with(path, &options).unwrap();
+        assert_eq!(std::fs::read(path).unwrap()[..2], *b"P6");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn unsupported_extension() {
+        let c = Canvas::new(2, 2);
+        let e = c.write_to_file("image.bmp").unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(!std::path::Path::new("image.bmp").exists());
+    }
 }
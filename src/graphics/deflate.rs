@@ -0,0 +1,278 @@
+/*
+ * A small zlib encoder. Data is compressed with LZ77 and written as a single
+ * deflate block using the fixed Huffman codes, which keeps the encoder simple
+ * while still shrinking the long runs of similar bytes found in images.
+ */
+
+const WINDOW_SIZE: usize = 32768;
+const WINDOW_MASK: usize = WINDOW_SIZE - 1;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+/* How many earlier positions to try when looking for a match */
+const MAX_CHAIN: usize = 64;
+const HASH_BITS: u32 = 15;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/* Packs bits least significant first, as deflate expects */
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            bytes: vec![],
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    fn write_bits(&mut self, value: u32, count: u32) {
+        self.buffer |= value << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /* Huffman codes are stored most significant bit first */
+    fn write_code(&mut self, code: u32, length: u32) {
+        let reversed = code.reverse_bits() >> (32 - length);
+        self.write_bits(reversed, length);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+fn write_literal(out: &mut BitWriter, symbol: u16) {
+    let symbol = symbol as u32;
+    match symbol {
+        0..=143 => out.write_code(0x30 + symbol, 8),
+        144..=255 => out.write_code(0x190 + symbol - 144, 9),
+        256..=279 => out.write_code(symbol - 256, 7),
+        _ => out.write_code(0xc0 + symbol - 280, 8),
+    }
+}
+
+/* Index of the last entry in `bases` that is not above `value` */
+fn find_code(bases: &[u16], value: usize) -> usize {
+    bases.iter().rposition(|b| *b as usize <= value).unwrap()
+}
+
+fn write_match(out: &mut BitWriter, length: usize, distance: usize) {
+    let code = find_code(&LENGTH_BASE, length);
+    write_literal(out, 257 + code as u16);
+    out.write_bits(
+        (length - LENGTH_BASE[code] as usize) as u32,
+        LENGTH_EXTRA[code] as u32,
+    );
+
+    let code = find_code(&DISTANCE_BASE, distance);
+    out.write_code(code as u32, 5);
+    out.write_bits(
+        (distance - DISTANCE_BASE[code] as usize) as u32,
+        DISTANCE_EXTRA[code] as u32,
+    );
+}
+
+/*
+ * Remembers where each three byte sequence was seen so earlier occurrences
+ * of the upcoming bytes can be found quickly.
+ */
+struct Matcher {
+    /* Most recent position for each hash */
+    head: Vec<usize>,
+    /*
+     * The position seen before each position with the same hash. Only
+     * positions within the window can be matched so the slots are reused.
+     */
+    previous: Vec<usize>,
+}
+
+impl Matcher {
+    fn new() -> Self {
+        Self {
+            head: vec![usize::MAX; 1 << HASH_BITS],
+            previous: vec![usize::MAX; WINDOW_SIZE],
+        }
+    }
+
+    fn hash(data: &[u8], i: usize) -> usize {
+        let v = (data[i] as u32) << 16 | (data[i + 1] as u32) << 8 | data[i + 2] as u32;
+        (v.wrapping_mul(0x9e37_79b1) >> (32 - HASH_BITS)) as usize
+    }
+
+    fn insert(&mut self, data: &[u8], i: usize) {
+        if i + MIN_MATCH <= data.len() {
+            let h = Self::hash(data, i);
+            self.previous[i & WINDOW_MASK] = self.head[h];
+            self.head[h] = i;
+        }
+    }
+
+    /* Longest earlier match for the bytes at `i` as a length and distance */
+    fn find(&self, data: &[u8], i: usize) -> (usize, usize) {
+        if i + MIN_MATCH > data.len() {
+            return (0, 0);
+        }
+        let max_length = MAX_MATCH.min(data.len() - i);
+        let mut best = (0, 0);
+        let mut candidate = self.head[Self::hash(data, i)];
+        let mut chain = 0;
+        while candidate != usize::MAX && i - candidate <= WINDOW_SIZE && chain < MAX_CHAIN {
+            let length = data[candidate..]
+                .iter()
+                .zip(&data[i..i + max_length])
+                .take_while(|(a, b)| a == b)
+                .count();
+            if length > best.0 {
+                best = (length, i - candidate);
+                if length == max_length {
+                    break;
+                }
+            }
+            let next = self.previous[candidate & WINDOW_MASK];
+            /* The slot may hold a newer position once the window moved on */
+            if next >= candidate {
+                break;
+            }
+            candidate = next;
+            chain += 1;
+        }
+        best
+    }
+}
+
+/* Raw deflate stream of `data` */
+pub fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut out = BitWriter::new();
+    /* A single final block using the fixed codes */
+    out.write_bits(1, 1);
+    out.write_bits(1, 2);
+
+    let mut matcher = Matcher::new();
+    let mut i = 0;
+    while i < data.len() {
+        let (length, distance) = matcher.find(data, i);
+        if length >= MIN_MATCH {
+            write_match(&mut out, length, distance);
+            for j in i..i + length {
+                matcher.insert(data, j);
+            }
+            i += length;
+        } else {
+            write_literal(&mut out, data[i] as u16);
+            matcher.insert(data, i);
+            i += 1;
+        }
+    }
+
+    write_literal(&mut out, 256);
+    out.finish()
+}
+
+pub fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+    /* The sums can't overflow within a chunk of this size */
+    for chunk in data.chunks(5552) {
+        for byte in chunk {
+            a += *byte as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    b << 16 | a
+}
+
+/* A zlib stream: a header, the deflated data and a checksum of the input */
+pub fn zlib(data: &[u8]) -> Vec<u8> {
+    /* 32K window, deflate, with check bits making the header a multiple of 31 */
+    let mut out = vec![0x78, 0x5e];
+    out.extend(deflate(data));
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::{adler32, deflate, zlib};
+
+    #[test]
+    fn adler32_checksum() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn deflating_nothing() {
+        assert_eq!(deflate(b""), vec![0x03, 0x00]);
+    }
+
+    #[test]
+    fn deflating_literals() {
+        /* Reference output from zlib for the same input and block type */
+        assert_eq!(deflate(b"a"), vec![0x4b, 0x04, 0x00]);
+        assert_eq!(deflate(b"abc"), vec![0x4b, 0x4c, 0x4a, 0x06, 0x00]);
+    }
+
+    #[test]
+    fn deflating_repeats() {
+        /* One literal followed by a match overlapping it */
+        assert_eq!(deflate(b"aaaaaaaaaa"), vec![0x4b, 0x84, 0x03, 0x00]);
+        let data = vec![7u8; 100_000];
+        assert!(deflate(&data).len() < 1000);
+    }
+
+    #[test]
+    fn matches_reach_back_a_full_window() {
+        /* A block of noise repeated right at the edge of the window */
+        let mut seed = 1u32;
+        let block: Vec<u8> = (0..1000)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (seed >> 16) as u8
+            })
+            .collect();
+        let mut data = block.clone();
+        data.resize(32768, 0);
+        data.extend(&block);
+        let repeated = deflate(&data).len();
+        data.insert(1000, 0);
+        let out_of_reach = deflate(&data).len();
+        assert!(repeated + 500 < out_of_reach);
+    }
+
+    #[test]
+    fn zlib_stream() {
+        let out = zlib(b"abc");
+        assert_eq!(out[..2], [0x78, 0x5e]);
+        assert_eq!((out[0] as u32 * 256 + out[1] as u32) % 31, 0);
+        assert_eq!(out[out.len() - 4..], adler32(b"abc").to_be_bytes());
+    }
+}
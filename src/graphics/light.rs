@@ -0,0 +1,228 @@
+use crate::math::Tuple;
+use crate::shapes::Shape;
+
+use super::Material;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointLight {
+    pub position: Tuple,
+    pub intensity: Tuple,
+}
+
+#[allow(dead_code)]
+impl PointLight {
+    pub fn new(position: Tuple, intensity: Tuple) -> Self {
+        Self {
+            position,
+            intensity,
+        }
+    }
+}
+
+/*
+ * Phong reflection model. Sums the ambient, diffuse and specular contribution
+ * of a single light at the given point.
+ */
+#[allow(dead_code)]
+pub fn lighting(
+    material: &Material,
+    object: &dyn Shape,
+    light: &PointLight,
+    point: &Tuple,
+    eyev: &Tuple,
+    normalv: &Tuple,
+    in_shadow: bool,
+) -> Tuple {
+    let black = Tuple::color(0.0, 0.0, 0.0);
+    let color = match &material.pattern {
+        Some(pattern) => pattern.pattern_at_shape(object, point),
+        None => material.color,
+    };
+    let effective_color = color * light.intensity;
+    let lightv = (light.position - *point).normal();
+    let ambient = effective_color * material.ambient;
+    if in_shadow {
+        /* Only ambient light reaches a point that is shadowed */
+        return ambient;
+    }
+
+    let light_dot_normal = lightv.dot(normalv);
+    if light_dot_normal < 0.0 {
+        /* The light is on the other side of the surface */
+        return ambient;
+    }
+
+    let diffuse = effective_color * material.diffuse * light_dot_normal;
+    let reflectv = (-lightv).reflect(normalv);
+    let reflect_dot_eye = reflectv.dot(eyev);
+    let specular = if reflect_dot_eye <= 0.0 {
+        black
+    } else {
+        light.intensity * material.specular * reflect_dot_eye.powf(material.shininess)
+    };
+
+    ambient + diffuse + specular
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::graphics::{lighting, Material, PointLight};
+    use crate::math::Tuple;
+    use crate::patterns::Stripe;
+    use crate::shapes::Sphere;
+
+    fn setup() -> (Material, Tuple) {
+        (Material::default(), Tuple::point(0.0, 0.0, 0.0))
+    }
+
+    #[test]
+    fn point_light() {
+        let intensity = Tuple::color(1.0, 1.0, 1.0);
+        let position = Tuple::point(0.0, 0.0, 0.0);
+        let light = PointLight::new(position, intensity);
+        assert_eq!(light.position, position);
+        assert_eq!(light.intensity, intensity);
+    }
+
+    #[test]
+    fn eye_between_light_and_surface() {
+        let (m, position) = setup();
+        let eyev = Tuple::vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+        let result = lighting(
+            &m,
+            &Sphere::new(),
+            &light,
+            &position,
+            &eyev,
+            &normalv,
+            false,
+        );
+        assert_eq!(result, Tuple::color(1.9, 1.9, 1.9));
+    }
+
+    #[test]
+    fn eye_offset_45_degrees() {
+        let (m, position) = setup();
+        let v = 2f64.sqrt() / 2.0;
+        let eyev = Tuple::vector(0.0, v, -v);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+        let result = lighting(
+            &m,
+            &Sphere::new(),
+            &light,
+            &position,
+            &eyev,
+            &normalv,
+            false,
+        );
+        assert_eq!(result, Tuple::color(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn light_offset_45_degrees() {
+        let (m, position) = setup();
+        let eyev = Tuple::vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let light = PointLight::new(Tuple::point(0.0, 10.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+        let result = lighting(
+            &m,
+            &Sphere::new(),
+            &light,
+            &position,
+            &eyev,
+            &normalv,
+            false,
+        );
+        assert_eq!(result.round(4), Tuple::color(0.7364, 0.7364, 0.7364));
+    }
+
+    #[test]
+    fn eye_in_reflection_path() {
+        let (m, position) = setup();
+        let v = 2f64.sqrt() / 2.0;
+        let eyev = Tuple::vector(0.0, -v, -v);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let light = PointLight::new(Tuple::point(0.0, 10.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+        let result = lighting(
+            &m,
+            &Sphere::new(),
+            &light,
+            &position,
+            &eyev,
+            &normalv,
+            false,
+        );
+        assert_eq!(result.round(4), Tuple::color(1.6364, 1.6364, 1.6364));
+    }
+
+    #[test]
+    fn light_behind_surface() {
+        let (m, position) = setup();
+        let eyev = Tuple::vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let light = PointLight::new(Tuple::point(0.0, 0.0, 10.0), Tuple::color(1.0, 1.0, 1.0));
+        let result = lighting(
+            &m,
+            &Sphere::new(),
+            &light,
+            &position,
+            &eyev,
+            &normalv,
+            false,
+        );
+        assert_eq!(result, Tuple::color(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn surface_in_shadow() {
+        let (m, position) = setup();
+        let eyev = Tuple::vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+        let result = lighting(&m, &Sphere::new(), &light, &position, &eyev, &normalv, true);
+        assert_eq!(result, Tuple::color(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn lighting_with_pattern() {
+        let m = Material {
+            pattern: Some(Arc::new(Stripe::new(
+                Tuple::color(1.0, 1.0, 1.0),
+                Tuple::color(0.0, 0.0, 0.0),
+            ))),
+            ambient: 1.0,
+            diffuse: 0.0,
+            specular: 0.0,
+            ..Default::default()
+        };
+        let object = Sphere::new();
+        let eyev = Tuple::vector(0.0, 0.0, -1.0);
+        let normalv = Tuple::vector(0.0, 0.0, -1.0);
+        let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+        let c1 = lighting(
+            &m,
+            &object,
+            &light,
+            &Tuple::point(0.9, 0.0, 0.0),
+            &eyev,
+            &normalv,
+            false,
+        );
+        let c2 = lighting(
+            &m,
+            &object,
+            &light,
+            &Tuple::point(1.1, 0.0, 0.0),
+            &eyev,
+            &normalv,
+            false,
+        );
+        assert_eq!(c1, Tuple::color(1.0, 1.0, 1.0));
+        assert_eq!(c2, Tuple::color(0.0, 0.0, 0.0));
+    }
+}
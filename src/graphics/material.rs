@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use crate::math::Tuple;
+use crate::patterns::Pattern;
+
+#[derive(Debug, Clone)]
+pub struct Material {
+    pub color: Tuple,
+    /* When set the pattern is used instead of the flat color */
+    pub pattern: Option<Arc<dyn Pattern>>,
+    pub ambient: f64,
+    pub diffuse: f64,
+    pub specular: f64,
+    pub shininess: f64,
+    pub reflective: f64,
+    pub transparency: f64,
+    pub refractive_index: f64,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self {
+            color: Tuple::color(1.0, 1.0, 1.0),
+            pattern: None,
+            ambient: 0.1,
+            diffuse: 0.9,
+            specular: 0.9,
+            shininess: 200.0,
+            reflective: 0.0,
+            transparency: 0.0,
+            refractive_index: 1.0,
+        }
+    }
+}
+
+impl PartialEq for Material {
+    fn eq(&self, o: &Self) -> bool {
+        let same_pattern = match (&self.pattern, &o.pattern) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        same_pattern
+            && self.color == o.color
+            && self.ambient == o.ambient
+            && self.diffuse == o.diffuse
+            && self.specular == o.specular
+            && self.shininess == o.shininess
+            && self.reflective == o.reflective
+            && self.transparency == o.transparency
+            && self.refractive_index == o.refractive_index
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::graphics::Material;
+    use crate::math::Tuple;
+
+    #[test]
+    fn default_material() {
+        let m = Material::default();
+        assert_eq!(m.color, Tuple::color(1.0, 1.0, 1.0));
+        assert!(m.pattern.is_none());
+        assert_eq!(m.ambient, 0.1);
+        assert_eq!(m.diffuse, 0.9);
+        assert_eq!(m.specular, 0.9);
+        assert_eq!(m.shininess, 200.0);
+        assert_eq!(m.reflective, 0.0);
+        assert_eq!(m.transparency, 0.0);
+        assert_eq!(m.refractive_index, 1.0);
+    }
+}
@@ -1,3 +1,9 @@
 mod canvas;
+mod deflate;
+mod light;
+mod material;
+mod png;
 
-pub use self::canvas::Canvas;
+pub use self::canvas::{BitDepth, Canvas, ImageOptions};
+pub use self::light::{lighting, PointLight};
+pub use self::material::Material;
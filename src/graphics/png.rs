@@ -0,0 +1,212 @@
+use std::io::{self, Write};
+
+use super::deflate::zlib;
+use super::{BitDepth, Canvas};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+/* Truecolor without alpha */
+const COLOR_TYPE_RGB: u8 = 2;
+
+fn crc_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    for (n, entry) in table.iter_mut().enumerate() {
+        let mut c = n as u32;
+        for _ in 0..8 {
+            c = if c & 1 == 1 {
+                0xedb8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+        }
+        *entry = c;
+    }
+    table
+}
+
+pub fn crc32(data: &[u8]) -> u32 {
+    let table = crc_table();
+    !data.iter().fold(!0u32, |c, byte| {
+        table[((c ^ *byte as u32) & 0xff) as usize] ^ (c >> 8)
+    })
+}
+
+fn write_chunk<W: Write>(out: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    let mut checked = kind.to_vec();
+    checked.extend_from_slice(data);
+    out.write_all(&checked)?;
+    out.write_all(&crc32(&checked).to_be_bytes())
+}
+
+/* Predicts a byte from its left, upper and upper left neighbours */
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = (
+        (p - a as i16).abs(),
+        (p - b as i16).abs(),
+        (p - c as i16).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/*
+ * Filters a row with each of the five PNG filters and keeps the one with
+ * the smallest sum of absolute differences, the heuristic suggested by the
+ * PNG specification.
+ */
+fn filter_row(row: &[u8], prior: &[u8], bpp: usize, out: &mut Vec<u8>) {
+    let mut best: Option<(u64, Vec<u8>)> = None;
+    for filter in 0..5u8 {
+        let mut filtered = Vec::with_capacity(row.len() + 1);
+        filtered.push(filter);
+        for i in 0..row.len() {
+            let a = if i >= bpp { row[i - bpp] } else { 0 };
+            let b = prior[i];
+            let c = if i >= bpp { prior[i - bpp] } else { 0 };
+            let predicted = match filter {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((a as u16 + b as u16) / 2) as u8,
+                _ => paeth(a, b, c),
+            };
+            filtered.push(row[i].wrapping_sub(predicted));
+        }
+        let cost = filtered[1..]
+            .iter()
+            .map(|v| (*v as i8).unsigned_abs() as u64)
+            .sum();
+        if best.as_ref().is_none_or(|(c, _)| cost < *c) {
+            best = Some((cost, filtered));
+        }
+    }
+    out.extend(best.unwrap().1);
+}
+
+fn channel(value: f64, depth: BitDepth) -> Vec<u8> {
+    let sample = depth.quantize(value);
+    match depth {
+        BitDepth::Eight => vec![sample as u8],
+        BitDepth::Sixteen => sample.to_be_bytes().to_vec(),
+    }
+}
+
+pub fn write_png<W: Write>(out: &mut W, canvas: &Canvas, depth: BitDepth) -> io::Result<()> {
+    out.write_all(&SIGNATURE)?;
+
+    let mut header = vec![];
+    header.extend((canvas.width as u32).to_be_bytes());
+    header.extend((canvas.height as u32).to_be_bytes());
+    /* Bit depth, color type, compression, filter method and no interlacing */
+    header.extend([depth.bits(), COLOR_TYPE_RGB, 0, 0, 0]);
+    write_chunk(out, b"IHDR", &header)?;
+
+    let bpp = 3 * depth.bits() as usize / 8;
+    let mut prior = vec![0; canvas.width * bpp];
+    let mut filtered = Vec::with_capacity((prior.len() + 1) * canvas.height);
+    for pixels in &canvas.matrix {
+        let row: Vec<u8> = pixels
+            .iter()
+            .flat_map(|p| [p.red(), p.green(), p.blue()])
+            .flat_map(|c| channel(c, depth))
+            .collect();
+        filter_row(&row, &prior, bpp, &mut filtered);
+        prior = row;
+    }
+    write_chunk(out, b"IDAT", &zlib(&filtered))?;
+
+    write_chunk(out, b"IEND", &[])
+}
+
+#[cfg(test)]
+mod test {
+    use crate::graphics::Canvas;
+    use crate::math::Tuple;
+
+    use crate::graphics::BitDepth;
+
+    use super::{crc32, filter_row, write_png};
+
+    fn png(canvas: &Canvas, depth: BitDepth) -> Vec<u8> {
+        let mut out = vec![];
+        write_png(&mut out, canvas, depth).unwrap();
+        out
+    }
+
+    /* Finds the chunks of a PNG file as a type and its data */
+    fn chunks(png: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let mut chunks = vec![];
+        let mut i = 8;
+        while i < png.len() {
+            let length = u32::from_be_bytes(png[i..i + 4].try_into().unwrap()) as usize;
+            let kind = String::from_utf8(png[i + 4..i + 8].to_vec()).unwrap();
+            let data = png[i + 8..i + 8 + length].to_vec();
+            let crc = u32::from_be_bytes(png[i + 8 + length..i + 12 + length].try_into().unwrap());
+            assert_eq!(crc, crc32(&png[i + 4..i + 8 + length]));
+            chunks.push((kind, data));
+            i += 12 + length;
+        }
+        chunks
+    }
+
+    #[test]
+    fn crc32_checksum() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(
+            crc32(b"The quick brown fox jumps over the lazy dog"),
+            0x414f_a339
+        );
+    }
+
+    #[test]
+    fn png_signature_and_chunks() {
+        let c = Canvas::new(5, 3);
+        let out = png(&c, BitDepth::Eight);
+        assert_eq!(
+            out[..8],
+            [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n']
+        );
+        let kinds: Vec<String> = chunks(&out).into_iter().map(|(k, _)| k).collect();
+        assert_eq!(kinds, vec!["IHDR", "IDAT", "IEND"]);
+    }
+
+    #[test]
+    fn png_header() {
+        let c = Canvas::new(5, 3);
+        let header = &chunks(&png(&c, BitDepth::Eight))[0].1;
+        assert_eq!(header, &vec![0, 0, 0, 5, 0, 0, 0, 3, 8, 2, 0, 0, 0]);
+        let header = &chunks(&png(&c, BitDepth::Sixteen))[0].1;
+        assert_eq!(header[8], 16);
+    }
+
+    #[test]
+    fn choosing_filters() {
+        let mut out = vec![];
+        /* A smooth ramp is best predicted from the left */
+        filter_row(&[10, 20, 30, 40], &[0, 0, 0, 0], 1, &mut out);
+        assert_eq!(out, vec![1, 10, 10, 10, 10]);
+        out.clear();
+        /* A row equal to the one above it is best predicted from above */
+        filter_row(&[9, 200, 3, 77], &[9, 200, 3, 77], 1, &mut out);
+        assert_eq!(out, vec![2, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn large_canvas_compresses() {
+        let mut c = Canvas::new(200, 200);
+        for y in 0..200 {
+            for x in 0..200 {
+                c.set_pixel(x, y, &Tuple::color(x as f64 / 200.0, 0.5, 1.0));
+            }
+        }
+        let out = png(&c, BitDepth::Eight);
+        assert!(out.len() < 200 * 200 * 3 / 10);
+    }
+}
@@ -1,7 +1,12 @@
 mod matrix;
+mod noise;
+mod ray;
+mod transform;
 mod tuple;
 mod util;
 
 pub use self::matrix::Matrix;
+pub use self::noise::perlin;
+pub use self::ray::Ray;
 pub use self::tuple::Tuple;
 pub use self::util::*;
@@ -0,0 +1,72 @@
+use super::{Matrix, Tuple};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Ray {
+    pub origin: Tuple,
+    pub direction: Tuple,
+}
+
+#[allow(dead_code)]
+impl Ray {
+    pub fn new(origin: Tuple, direction: Tuple) -> Self {
+        /* Sanity check to prevent swapped arguments */
+        debug_assert!(origin.is_point());
+        debug_assert!(direction.is_vector());
+
+        Self { origin, direction }
+    }
+
+    pub fn position(&self, t: f64) -> Tuple {
+        self.origin + self.direction * t
+    }
+
+    pub fn transform(&self, m: &Matrix) -> Self {
+        Self {
+            origin: m * self.origin,
+            direction: m * self.direction,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::{Matrix, Tuple};
+
+    use super::Ray;
+
+    #[test]
+    fn creating_ray() {
+        let origin = Tuple::point(1.0, 2.0, 3.0);
+        let direction = Tuple::vector(4.0, 5.0, 6.0);
+        let r = Ray::new(origin, direction);
+        assert_eq!(r.origin, origin);
+        assert_eq!(r.direction, direction);
+    }
+
+    #[test]
+    fn position() {
+        let r = Ray::new(Tuple::point(2.0, 3.0, 4.0), Tuple::vector(1.0, 0.0, 0.0));
+        assert_eq!(r.position(0.0), Tuple::point(2.0, 3.0, 4.0));
+        assert_eq!(r.position(1.0), Tuple::point(3.0, 3.0, 4.0));
+        assert_eq!(r.position(-1.0), Tuple::point(1.0, 3.0, 4.0));
+        assert_eq!(r.position(2.5), Tuple::point(4.5, 3.0, 4.0));
+    }
+
+    #[test]
+    fn translating_ray() {
+        let r = Ray::new(Tuple::point(1.0, 2.0, 3.0), Tuple::vector(0.0, 1.0, 0.0));
+        let m = Matrix::translation(3.0, 4.0, 5.0);
+        let r2 = r.transform(&m);
+        assert_eq!(r2.origin, Tuple::point(4.0, 6.0, 8.0));
+        assert_eq!(r2.direction, Tuple::vector(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn scaling_ray() {
+        let r = Ray::new(Tuple::point(1.0, 2.0, 3.0), Tuple::vector(0.0, 1.0, 0.0));
+        let m = Matrix::scaling(2.0, 3.0, 4.0);
+        let r2 = r.transform(&m);
+        assert_eq!(r2.origin, Tuple::point(2.0, 6.0, 12.0));
+        assert_eq!(r2.direction, Tuple::vector(0.0, 3.0, 0.0));
+    }
+}
@@ -0,0 +1,304 @@
+use super::{Matrix, Tuple};
+
+/*
+ * Transformation constructors. The chaining methods multiply the new
+ * transformation on the left hand side, so transformations are applied in the
+ * order they are written:
+ *
+ *     Matrix::identity().rotate_x(a).scale(x, y, z).translate(x, y, z)
+ *
+ * rotates first, then scales and finally translates.
+ */
+#[allow(dead_code)]
+impl Matrix {
+    pub fn translation(x: f64, y: f64, z: f64) -> Self {
+        let mut m = Matrix::identity();
+        m[0][3] = x;
+        m[1][3] = y;
+        m[2][3] = z;
+        m
+    }
+
+    pub fn scaling(x: f64, y: f64, z: f64) -> Self {
+        let mut m = Matrix::identity();
+        m[0][0] = x;
+        m[1][1] = y;
+        m[2][2] = z;
+        m
+    }
+
+    pub fn rotation_x(r: f64) -> Self {
+        let mut m = Matrix::identity();
+        m[1][1] = r.cos();
+        m[1][2] = -r.sin();
+        m[2][1] = r.sin();
+        m[2][2] = r.cos();
+        m
+    }
+
+    pub fn rotation_y(r: f64) -> Self {
+        let mut m = Matrix::identity();
+        m[0][0] = r.cos();
+        m[0][2] = r.sin();
+        m[2][0] = -r.sin();
+        m[2][2] = r.cos();
+        m
+    }
+
+    pub fn rotation_z(r: f64) -> Self {
+        let mut m = Matrix::identity();
+        m[0][0] = r.cos();
+        m[0][1] = -r.sin();
+        m[1][0] = r.sin();
+        m[1][1] = r.cos();
+        m
+    }
+
+    pub fn shearing(xy: f64, xz: f64, yx: f64, yz: f64, zx: f64, zy: f64) -> Self {
+        let mut m = Matrix::identity();
+        m[0][1] = xy;
+        m[0][2] = xz;
+        m[1][0] = yx;
+        m[1][2] = yz;
+        m[2][0] = zx;
+        m[2][1] = zy;
+        m
+    }
+
+    /*
+     * Orients the world relative to an eye positioned at `from` looking
+     * toward `to`, with `up` roughly pointing upwards.
+     */
+    pub fn view_transform(from: &Tuple, to: &Tuple, up: &Tuple) -> Self {
+        let forward = (*to - *from).normal();
+        let left = forward.cross(&up.normal());
+        let true_up = left.cross(&forward);
+        let orientation = Matrix::new(vec![
+            vec![left.x(), left.y(), left.z(), 0.0],
+            vec![true_up.x(), true_up.y(), true_up.z(), 0.0],
+            vec![-forward.x(), -forward.y(), -forward.z(), 0.0],
+            vec![0.0, 0.0, 0.0, 1.0],
+        ]);
+        orientation * Matrix::translation(-from.x(), -from.y(), -from.z())
+    }
+
+    pub fn translate(self, x: f64, y: f64, z: f64) -> Self {
+        Matrix::translation(x, y, z) * self
+    }
+
+    pub fn scale(self, x: f64, y: f64, z: f64) -> Self {
+        Matrix::scaling(x, y, z) * self
+    }
+
+    pub fn rotate_x(self, r: f64) -> Self {
+        Matrix::rotation_x(r) * self
+    }
+
+    pub fn rotate_y(self, r: f64) -> Self {
+        Matrix::rotation_y(r) * self
+    }
+
+    pub fn rotate_z(self, r: f64) -> Self {
+        Matrix::rotation_z(r) * self
+    }
+
+    pub fn shear(self, xy: f64, xz: f64, yx: f64, yz: f64, zx: f64, zy: f64) -> Self {
+        Matrix::shearing(xy, xz, yx, yz, zx, zy) * self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+    use crate::math::{Matrix, Tuple};
+
+    #[test]
+    fn translation() {
+        let t = Matrix::translation(5.0, -3.0, 2.0);
+        let p = Tuple::point(-3.0, 4.0, 5.0);
+        assert_eq!(t * p, Tuple::point(2.0, 1.0, 7.0));
+    }
+
+    #[test]
+    fn inverse_translation() {
+        let t = Matrix::translation(5.0, -3.0, 2.0);
+        let p = Tuple::point(-3.0, 4.0, 5.0);
+        assert_eq!(t.inverse() * p, Tuple::point(-8.0, 7.0, 3.0));
+    }
+
+    #[test]
+    fn translation_ignores_vectors() {
+        let t = Matrix::translation(5.0, -3.0, 2.0);
+        let v = Tuple::vector(-3.0, 4.0, 5.0);
+        assert_eq!(t * v, v);
+    }
+
+    #[test]
+    fn scaling_point() {
+        let t = Matrix::scaling(2.0, 3.0, 4.0);
+        let p = Tuple::point(-4.0, 6.0, 8.0);
+        assert_eq!(t * p, Tuple::point(-8.0, 18.0, 32.0));
+    }
+
+    #[test]
+    fn scaling_vector() {
+        let t = Matrix::scaling(2.0, 3.0, 4.0);
+        let v = Tuple::vector(-4.0, 6.0, 8.0);
+        assert_eq!(t * v, Tuple::vector(-8.0, 18.0, 32.0));
+    }
+
+    #[test]
+    fn inverse_scaling() {
+        let t = Matrix::scaling(2.0, 3.0, 4.0);
+        let v = Tuple::vector(-4.0, 6.0, 8.0);
+        assert_eq!(t.inverse() * v, Tuple::vector(-2.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn reflection() {
+        let t = Matrix::scaling(-1.0, 1.0, 1.0);
+        let p = Tuple::point(2.0, 3.0, 4.0);
+        assert_eq!(t * p, Tuple::point(-2.0, 3.0, 4.0));
+    }
+
+    #[test]
+    fn rotation_x() {
+        let p = Tuple::point(0.0, 1.0, 0.0);
+        let half_quarter = Matrix::rotation_x(PI / 4.0);
+        let full_quarter = Matrix::rotation_x(PI / 2.0);
+        assert_eq!(
+            (half_quarter * p).round(5),
+            Tuple::point(0.0, FRAC_1_SQRT_2, FRAC_1_SQRT_2).round(5)
+        );
+        assert_eq!((full_quarter * p).round(5), Tuple::point(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn inverse_rotation_x() {
+        let p = Tuple::point(0.0, 1.0, 0.0);
+        let half_quarter = Matrix::rotation_x(PI / 4.0);
+        assert_eq!(
+            (half_quarter.inverse() * p).round(5),
+            Tuple::point(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2).round(5)
+        );
+    }
+
+    #[test]
+    fn rotation_y() {
+        let p = Tuple::point(0.0, 0.0, 1.0);
+        let half_quarter = Matrix::rotation_y(PI / 4.0);
+        let full_quarter = Matrix::rotation_y(PI / 2.0);
+        assert_eq!(
+            (half_quarter * p).round(5),
+            Tuple::point(FRAC_1_SQRT_2, 0.0, FRAC_1_SQRT_2).round(5)
+        );
+        assert_eq!((full_quarter * p).round(5), Tuple::point(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn rotation_z() {
+        let p = Tuple::point(0.0, 1.0, 0.0);
+        let half_quarter = Matrix::rotation_z(PI / 4.0);
+        let full_quarter = Matrix::rotation_z(PI / 2.0);
+        assert_eq!(
+            (half_quarter * p).round(5),
+            Tuple::point(-FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0).round(5)
+        );
+        assert_eq!((full_quarter * p).round(5), Tuple::point(-1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn shearing() {
+        let p = Tuple::point(2.0, 3.0, 4.0);
+        let cases = [
+            ((1.0, 0.0, 0.0, 0.0, 0.0, 0.0), Tuple::point(5.0, 3.0, 4.0)),
+            ((0.0, 1.0, 0.0, 0.0, 0.0, 0.0), Tuple::point(6.0, 3.0, 4.0)),
+            ((0.0, 0.0, 1.0, 0.0, 0.0, 0.0), Tuple::point(2.0, 5.0, 4.0)),
+            ((0.0, 0.0, 0.0, 1.0, 0.0, 0.0), Tuple::point(2.0, 7.0, 4.0)),
+            ((0.0, 0.0, 0.0, 0.0, 1.0, 0.0), Tuple::point(2.0, 3.0, 6.0)),
+            ((0.0, 0.0, 0.0, 0.0, 0.0, 1.0), Tuple::point(2.0, 3.0, 7.0)),
+        ];
+        for ((xy, xz, yx, yz, zx, zy), expected) in cases {
+            let t = Matrix::shearing(xy, xz, yx, yz, zx, zy);
+            assert_eq!(t * p, expected);
+        }
+    }
+
+    #[test]
+    fn transformations_in_sequence() {
+        let p = Tuple::point(1.0, 0.0, 1.0);
+        let a = Matrix::rotation_x(PI / 2.0);
+        let b = Matrix::scaling(5.0, 5.0, 5.0);
+        let c = Matrix::translation(10.0, 5.0, 7.0);
+
+        let p2 = a * p;
+        assert_eq!(p2.round(5), Tuple::point(1.0, -1.0, 0.0));
+        let p3 = b * p2;
+        assert_eq!(p3.round(5), Tuple::point(5.0, -5.0, 0.0));
+        let p4 = c * p3;
+        assert_eq!(p4.round(5), Tuple::point(15.0, 0.0, 7.0));
+    }
+
+    #[test]
+    fn chained_transformations() {
+        let p = Tuple::point(1.0, 0.0, 1.0);
+        let a = Matrix::rotation_x(PI / 2.0);
+        let b = Matrix::scaling(5.0, 5.0, 5.0);
+        let c = Matrix::translation(10.0, 5.0, 7.0);
+        let t = c * b * a;
+        assert_eq!((t * p).round(5), Tuple::point(15.0, 0.0, 7.0));
+    }
+
+    #[test]
+    fn fluent_transformations() {
+        let p = Tuple::point(1.0, 0.0, 1.0);
+        let t = Matrix::identity()
+            .rotate_x(PI / 2.0)
+            .scale(5.0, 5.0, 5.0)
+            .translate(10.0, 5.0, 7.0);
+        assert_eq!((t * p).round(5), Tuple::point(15.0, 0.0, 7.0));
+    }
+
+    #[test]
+    fn view_transform_default_orientation() {
+        let from = Tuple::point(0.0, 0.0, 0.0);
+        let to = Tuple::point(0.0, 0.0, -1.0);
+        let up = Tuple::vector(0.0, 1.0, 0.0);
+        let t = Matrix::view_transform(&from, &to, &up);
+        assert_eq!(t, Matrix::identity());
+    }
+
+    #[test]
+    fn view_transform_positive_z() {
+        let from = Tuple::point(0.0, 0.0, 0.0);
+        let to = Tuple::point(0.0, 0.0, 1.0);
+        let up = Tuple::vector(0.0, 1.0, 0.0);
+        let t = Matrix::view_transform(&from, &to, &up);
+        assert_eq!(t, Matrix::scaling(-1.0, 1.0, -1.0));
+    }
+
+    #[test]
+    fn view_transform_moves_world() {
+        let from = Tuple::point(0.0, 0.0, 8.0);
+        let to = Tuple::point(0.0, 0.0, 0.0);
+        let up = Tuple::vector(0.0, 1.0, 0.0);
+        let t = Matrix::view_transform(&from, &to, &up);
+        assert_eq!(t, Matrix::translation(0.0, 0.0, -8.0));
+    }
+
+    #[test]
+    fn arbitrary_view_transform() {
+        let from = Tuple::point(1.0, 3.0, 2.0);
+        let to = Tuple::point(4.0, -2.0, 8.0);
+        let up = Tuple::vector(1.0, 1.0, 0.0);
+        let t = Matrix::view_transform(&from, &to, &up);
+        let expected = Matrix::new(vec![
+            vec![-0.50709, 0.50709, 0.67612, -2.36643],
+            vec![0.76772, 0.60609, 0.12122, -2.82843],
+            vec![-0.35857, 0.59761, -0.71714, 0.00000],
+            vec![0.00000, 0.00000, 0.00000, 1.00000],
+        ]);
+        assert_eq!(t.round(5), expected);
+    }
+}
@@ -3,7 +3,7 @@ const POINT: f64 = 1.0;
 
 use std::ops::{Deref, DerefMut};
 
-use crate::math::util::epsilon_eq as feq;
+use crate::math::util::{epsilon_eq as feq, round};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Tuple {
@@ -99,6 +99,16 @@ impl Tuple {
             ],
         }
     }
+
+    pub fn reflect(&self, normal: &Tuple) -> Self {
+        *self - *normal * 2.0 * self.dot(normal)
+    }
+
+    pub fn round(&self, decimal_count: u32) -> Self {
+        let mut clone = *self;
+        clone.iter_mut().for_each(|v| *v = round(*v, decimal_count));
+        clone
+    }
 }
 
 impl Default for Tuple {
@@ -408,6 +418,21 @@ mod tests {
         assert_eq!(b.cross(&a), Tuple::vector(1.0, -2.0, 1.0));
     }
 
+    #[test]
+    fn reflect_at_45_degrees() {
+        let v = Tuple::vector(1.0, -1.0, 0.0);
+        let n = Tuple::vector(0.0, 1.0, 0.0);
+        assert_eq!(v.reflect(&n), Tuple::vector(1.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn reflect_off_slanted_surface() {
+        let v = Tuple::vector(0.0, -1.0, 0.0);
+        let s = 2f64.sqrt() / 2.0;
+        let n = Tuple::vector(s, s, 0.0);
+        assert_eq!(v.reflect(&n).round(5), Tuple::vector(1.0, 0.0, 0.0));
+    }
+
     #[test]
     fn color() {
         let c = Tuple::color(-0.5, 0.4, 1.7);
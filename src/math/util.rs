@@ -1,5 +1,9 @@
+/* Tolerance used when comparing or offsetting points in the scene */
+pub const EPSILON: f64 = 0.00001;
+
 pub fn epsilon_eq(a: f64, b: f64) -> bool {
-    (a - b).abs() < std::f64::EPSILON
+    /* Exact equality first so infinities compare equal */
+    a == b || (a - b).abs() < f64::EPSILON
 }
 
 pub fn round(v: f64, decimals: u32) -> f64 {
@@ -20,4 +24,10 @@ mod test {
     fn epsilon_compare() {
         assert!(epsilon_eq(0.1f64 + 0.2f64, 0.3f64));
     }
+
+    #[test]
+    fn infinity_compare() {
+        assert!(epsilon_eq(f64::INFINITY, f64::INFINITY));
+        assert!(!epsilon_eq(f64::INFINITY, f64::NEG_INFINITY));
+    }
 }
@@ -0,0 +1,8 @@
+mod obj;
+mod scene;
+mod yaml;
+
+#[allow(unused_imports)]
+pub use self::obj::{Face, ObjError, ObjFile};
+pub use self::scene::{Scene, SceneError};
+pub use self::yaml::{Yaml, YamlError};
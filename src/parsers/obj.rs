@@ -0,0 +1,394 @@
+use std::{error, fmt, fs, io};
+
+use crate::math::Tuple;
+use crate::shapes::{Group, Shape, SmoothTriangle, Triangle};
+
+#[derive(Debug)]
+pub enum ObjError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::Io(e) => write!(f, "{}", e),
+            ObjError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl error::Error for ObjError {}
+
+impl From<io::Error> for ObjError {
+    fn from(e: io::Error) -> Self {
+        ObjError::Io(e)
+    }
+}
+
+/*
+ * A triangle of a mesh as indices into the vertex and normal lists of the
+ * file. Indices are zero based even though the file counts from one.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Face {
+    pub vertices: [usize; 3],
+    pub normals: Option<[usize; 3]>,
+}
+
+/*
+ * The contents of a Wavefront OBJ file. Only vertices, vertex normals, faces
+ * and groups are understood, any other statement is skipped and counted.
+ */
+#[derive(Debug, Default)]
+pub struct ObjFile {
+    pub vertices: Vec<Tuple>,
+    pub normals: Vec<Tuple>,
+    /* Number of statements that were skipped */
+    pub ignored: usize,
+    faces: Vec<Face>,
+    groups: Vec<(String, Vec<Face>)>,
+}
+
+#[allow(dead_code)]
+impl ObjFile {
+    pub fn load(path: &str) -> Result<Self, ObjError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(input: &str) -> Result<Self, ObjError> {
+        let mut obj = Self::default();
+        /* Index into `groups` of the group faces go to, None is the default */
+        let mut current: Option<usize> = None;
+
+        for (i, line) in input.lines().enumerate() {
+            let error = |message: String| ObjError::Parse {
+                line: i + 1,
+                message,
+            };
+            let mut words = line.split_whitespace();
+            let Some(keyword) = words.next() else {
+                continue;
+            };
+            let args: Vec<&str> = words.collect();
+
+            match keyword {
+                _ if keyword.starts_with('#') => {}
+                "v" => obj.vertices.push(parse_point(&args).map_err(error)?),
+                "vn" => obj.normals.push(parse_vector(&args).map_err(error)?),
+                "f" => {
+                    let faces = obj.parse_face(&args).map_err(error)?;
+                    match current {
+                        Some(g) => obj.groups[g].1.extend(faces),
+                        None => obj.faces.extend(faces),
+                    }
+                }
+                "g" => {
+                    current = if args.is_empty() {
+                        None
+                    } else {
+                        let name = args.join(" ");
+                        match obj.groups.iter().position(|(n, _)| *n == name) {
+                            Some(g) => Some(g),
+                            None => {
+                                obj.groups.push((name, vec![]));
+                                Some(obj.groups.len() - 1)
+                            }
+                        }
+                    }
+                }
+                _ => obj.ignored += 1,
+            }
+        }
+
+        Ok(obj)
+    }
+
+    /* Faces that appear before any named group */
+    pub fn faces(&self) -> &[Face] {
+        &self.faces
+    }
+
+    pub fn group(&self, name: &str) -> Option<&[Face]> {
+        self.groups
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, faces)| faces.as_slice())
+    }
+
+    pub fn group_names(&self) -> impl Iterator<Item = &str> {
+        self.groups.iter().map(|(n, _)| n.as_str())
+    }
+
+    /*
+     * Builds a group holding the triangles of the default group directly and
+     * a child group for every named group.
+     */
+    pub fn to_group(&self) -> Group {
+        let mut group = Group::new();
+        for face in &self.faces {
+            group.add_boxed(self.triangle(face));
+        }
+        for (_, faces) in &self.groups {
+            let mut child = Group::new();
+            for face in faces {
+                child.add_boxed(self.triangle(face));
+            }
+            group.add_child(child);
+        }
+        group
+    }
+
+    fn triangle(&self, face: &Face) -> Box<dyn Shape> {
+        let [p1, p2, p3] = face.vertices.map(|i| self.vertices[i]);
+        match face.normals {
+            Some(normals) => {
+                let [n1, n2, n3] = normals.map(|i| self.normals[i]);
+                Box::new(SmoothTriangle::new(p1, p2, p3, n1, n2, n3))
+            }
+            None => Box::new(Triangle::new(p1, p2, p3)),
+        }
+    }
+
+    /* Polygons are split into a fan of triangles around their first vertex */
+    fn parse_face(&self, args: &[&str]) -> Result<Vec<Face>, String> {
+        if args.len() < 3 {
+            return Err(format!(
+                "face needs at least 3 vertices, got {}",
+                args.len()
+            ));
+        }
+
+        let mut vertices = vec![];
+        let mut normals = vec![];
+        for arg in args {
+            let mut parts = arg.split('/');
+            let vertex = parts.next().unwrap_or_default();
+            vertices.push(resolve_index(vertex, self.vertices.len(), "vertex")?);
+            /* Texture coordinates are not supported so skip them */
+            parts.next();
+            if let Some(normal) = parts.next().filter(|n| !n.is_empty()) {
+                normals.push(resolve_index(normal, self.normals.len(), "normal")?);
+            }
+        }
+        /* Only smooth the face if every vertex has a normal */
+        let has_normals = normals.len() == vertices.len();
+
+        Ok((1..vertices.len() - 1)
+            .map(|i| Face {
+                vertices: [vertices[0], vertices[i], vertices[i + 1]],
+                normals: has_normals.then(|| [normals[0], normals[i], normals[i + 1]]),
+            })
+            .collect())
+    }
+}
+
+fn parse_numbers(args: &[&str]) -> Result<Vec<f64>, String> {
+    args.iter()
+        .map(|a| a.parse().map_err(|_| format!("invalid number '{}'", a)))
+        .collect()
+}
+
+fn parse_point(args: &[&str]) -> Result<Tuple, String> {
+    /* A fourth weight component is allowed but has no meaning here */
+    match parse_numbers(args)?[..] {
+        [x, y, z] | [x, y, z, _] => Ok(Tuple::point(x, y, z)),
+        _ => Err(format!("vertex needs 3 coordinates, got {}", args.len())),
+    }
+}
+
+fn parse_vector(args: &[&str]) -> Result<Tuple, String> {
+    match parse_numbers(args)?[..] {
+        [x, y, z] => Ok(Tuple::vector(x, y, z)),
+        _ => Err(format!("normal needs 3 coordinates, got {}", args.len())),
+    }
+}
+
+/*
+ * Converts a one based index into a zero based one, negative indices count
+ * back from the most recently defined element.
+ */
+fn resolve_index(index: &str, count: usize, kind: &str) -> Result<usize, String> {
+    let n: i64 = index
+        .parse()
+        .map_err(|_| format!("invalid {} index '{}'", kind, index))?;
+    let resolved = if n < 0 { count as i64 + n } else { n - 1 };
+    if n == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(format!("{} index {} out of range", kind, n));
+    }
+    Ok(resolved as usize)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::{Ray, Tuple};
+    use crate::parsers::{Face, ObjError, ObjFile};
+    use crate::shapes::Shape;
+
+    fn parse_error(input: &str) -> (usize, String) {
+        match ObjFile::parse(input) {
+            Err(ObjError::Parse { line, message }) => (line, message),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn ignoring_unrecognized_lines() {
+        let gibberish = "There was a young lady named Bright\n\
+                         who traveled much faster than light.\n\
+                         She set out one day\n\
+                         in a relative way,\n\
+                         and came back the previous night.\n";
+        let obj = ObjFile::parse(gibberish).unwrap();
+        assert_eq!(obj.ignored, 5);
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_not_counted() {
+        let obj = ObjFile::parse("# a comment\n\n   \nv 1 2 3\n").unwrap();
+        assert_eq!(obj.ignored, 0);
+        assert_eq!(obj.vertices.len(), 1);
+    }
+
+    #[test]
+    fn vertex_records() {
+        let obj = ObjFile::parse("v -1 1 0\nv -1.0000 0.5000 0.0000\nv 1 0 0\nv 1 1 0\n").unwrap();
+        assert_eq!(obj.vertices[0], Tuple::point(-1.0, 1.0, 0.0));
+        assert_eq!(obj.vertices[1], Tuple::point(-1.0, 0.5, 0.0));
+        assert_eq!(obj.vertices[2], Tuple::point(1.0, 0.0, 0.0));
+        assert_eq!(obj.vertices[3], Tuple::point(1.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn parsing_triangle_faces() {
+        let obj =
+            ObjFile::parse("v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\n\nf 1 2 3\nf 1 3 4\n").unwrap();
+        assert_eq!(
+            obj.faces(),
+            &[
+                Face {
+                    vertices: [0, 1, 2],
+                    normals: None
+                },
+                Face {
+                    vertices: [0, 2, 3],
+                    normals: None
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn triangulating_polygons() {
+        let obj = ObjFile::parse("v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\nv 0 2 0\n\nf 1 2 3 4 5\n")
+            .unwrap();
+        let vertices: Vec<[usize; 3]> = obj.faces().iter().map(|f| f.vertices).collect();
+        assert_eq!(vertices, vec![[0, 1, 2], [0, 2, 3], [0, 3, 4]]);
+    }
+
+    #[test]
+    fn triangles_in_groups() {
+        let obj = ObjFile::parse(
+            "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\n\ng FirstGroup\nf 1 2 3\ng SecondGroup\nf 1 3 4\n",
+        )
+        .unwrap();
+        assert!(obj.faces().is_empty());
+        assert_eq!(obj.group("FirstGroup").unwrap()[0].vertices, [0, 1, 2]);
+        assert_eq!(obj.group("SecondGroup").unwrap()[0].vertices, [0, 2, 3]);
+        assert_eq!(
+            obj.group_names().collect::<Vec<_>>(),
+            vec!["FirstGroup", "SecondGroup"]
+        );
+    }
+
+    #[test]
+    fn reopening_group() {
+        let obj =
+            ObjFile::parse("v 0 1 0\nv -1 0 0\nv 1 0 0\ng a\nf 1 2 3\ng\nf 1 2 3\ng a\nf 3 2 1\n")
+                .unwrap();
+        assert_eq!(obj.faces().len(), 1);
+        assert_eq!(obj.group("a").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn converting_to_group() {
+        let obj = ObjFile::parse(
+            "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\n\nf 1 2 3\ng FirstGroup\nf 1 2 3\ng SecondGroup\nf 1 3 4\n",
+        )
+        .unwrap();
+        let g = obj.to_group();
+        assert_eq!(g.children().len(), 3);
+        let r = Ray::new(Tuple::point(0.5, 0.75, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = g.intersect(&r);
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t, 2.0);
+    }
+
+    #[test]
+    fn vertex_normal_records() {
+        let obj = ObjFile::parse("vn 0 0 1\nvn 0.707 0 -0.707\nvn 1 2 3\n").unwrap();
+        assert_eq!(obj.normals[0], Tuple::vector(0.0, 0.0, 1.0));
+        assert_eq!(obj.normals[1], Tuple::vector(0.707, 0.0, -0.707));
+        assert_eq!(obj.normals[2], Tuple::vector(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn faces_with_normals() {
+        let obj = ObjFile::parse(
+            "v 0 1 0\nv -1 0 0\nv 1 0 0\n\nvn -1 0 0\nvn 1 0 0\nvn 0 1 0\n\nf 1//3 2//1 3//2\nf 1/0/3 2/102/1 3/14/2\n",
+        )
+        .unwrap();
+        let expected = Face {
+            vertices: [0, 1, 2],
+            normals: Some([2, 0, 1]),
+        };
+        assert_eq!(obj.faces(), &[expected, expected]);
+
+        let g = obj.to_group();
+        let r = Ray::new(Tuple::point(0.0, 0.5, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = g.intersect(&r);
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert_eq!(comps.normalv, Tuple::vector(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn negative_indices() {
+        let obj = ObjFile::parse("v 0 1 0\nv -1 0 0\nv 1 0 0\nf -3 -2 -1\n").unwrap();
+        assert_eq!(obj.faces()[0].vertices, [0, 1, 2]);
+    }
+
+    #[test]
+    fn invalid_number() {
+        let (line, message) = parse_error("v 1 2 3\nv 1 x 3\n");
+        assert_eq!(line, 2);
+        assert_eq!(message, "invalid number 'x'");
+    }
+
+    #[test]
+    fn missing_coordinates() {
+        let (line, _) = parse_error("v 1 2\n");
+        assert_eq!(line, 1);
+    }
+
+    #[test]
+    fn face_index_out_of_range() {
+        let (line, message) = parse_error("v 0 1 0\nv -1 0 0\nv 1 0 0\n\nf 1 2 4\n");
+        assert_eq!(line, 5);
+        assert_eq!(message, "vertex index 4 out of range");
+        let (_, message) = parse_error("v 0 1 0\nv -1 0 0\nv 1 0 0\nf 0 1 2\n");
+        assert_eq!(message, "vertex index 0 out of range");
+    }
+
+    #[test]
+    fn face_with_too_few_vertices() {
+        let (line, message) = parse_error("v 0 1 0\nv -1 0 0\nf 1 2\n");
+        assert_eq!(line, 3);
+        assert_eq!(message, "face needs at least 3 vertices, got 2");
+    }
+
+    #[test]
+    fn error_message_includes_line() {
+        let e = ObjFile::parse("vn 1 2\n").unwrap_err();
+        assert_eq!(e.to_string(), "line 1: normal needs 3 coordinates, got 2");
+    }
+}
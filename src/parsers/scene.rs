@@ -0,0 +1,1038 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::{error, fmt, fs, io};
+
+use crate::graphics::{Material, PointLight};
+use crate::math::{Matrix, Tuple, EPSILON};
+use crate::patterns::{Blend, Checkers, Gradient, Pattern, Perturbed, Ring, Solid, Stripe};
+use crate::scene::{Camera, World};
+use crate::shapes::{
+    Cone, Csg, CsgOperation, Cube, Cylinder, Group, Plane, Shape, SmoothTriangle, Sphere, Triangle,
+    BVH_THRESHOLD,
+};
+
+use super::{ObjFile, Yaml, YamlError};
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(io::Error),
+    Yaml(YamlError),
+    /* The document is valid YAML but not a valid scene */
+    Invalid { path: String, message: String },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io(e) => write!(f, "{}", e),
+            SceneError::Yaml(e) => write!(f, "{}", e),
+            SceneError::Invalid { path, message } if path.is_empty() => write!(f, "{}", message),
+            SceneError::Invalid { path, message } => write!(f, "{}: {}", path, message),
+        }
+    }
+}
+
+impl error::Error for SceneError {}
+
+impl From<io::Error> for SceneError {
+    fn from(e: io::Error) -> Self {
+        SceneError::Io(e)
+    }
+}
+
+impl From<YamlError> for SceneError {
+    fn from(e: YamlError) -> Self {
+        SceneError::Yaml(e)
+    }
+}
+
+type Result<T> = std::result::Result<T, SceneError>;
+
+fn invalid(path: &str, message: impl Into<String>) -> SceneError {
+    SceneError::Invalid {
+        path: path.to_string(),
+        message: message.into(),
+    }
+}
+
+/*
+ * A world and the camera to render it with, loaded from a YAML scene file.
+ * The file is a list of items that either `add` a camera, light or shape to
+ * the scene or `define` a named material or transform that later items can
+ * refer to. Definitions may `extend` an earlier one.
+ */
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct Scene {
+    pub world: World,
+    pub camera: Camera,
+}
+
+#[allow(dead_code)]
+impl Scene {
+    pub fn load(path: &str) -> Result<Self> {
+        let input = fs::read_to_string(path)?;
+        /* Files referenced by the scene are relative to it */
+        let dir = Path::new(path).parent().unwrap_or(Path::new("."));
+        Self::parse_in(&input, dir)
+    }
+
+    pub fn parse(input: &str) -> Result<Self> {
+        Self::parse_in(input, Path::new("."))
+    }
+
+    fn parse_in(input: &str, dir: &Path) -> Result<Self> {
+        let yaml = Yaml::parse(input)?;
+        let mut loader = Loader {
+            defines: HashMap::new(),
+            resolving: RefCell::new(vec![]),
+            dir: dir.to_path_buf(),
+        };
+        let mut world = World::new();
+        let mut camera = None;
+
+        let items = match &yaml {
+            Yaml::Sequence(items) => items.as_slice(),
+            Yaml::Null => &[],
+            other => {
+                return Err(invalid(
+                    "",
+                    format!("expected a sequence of items, found {}", other.type_name()),
+                ))
+            }
+        };
+
+        for (i, item) in items.iter().enumerate() {
+            let path = format!("[{}]", i);
+            if item.get("define").is_some() {
+                loader.define(item, &path)?;
+                continue;
+            }
+            let kind = match item.get("add") {
+                Some(kind) => string(kind, &format!("{}.add", path))?,
+                None => return Err(invalid(&path, "expected an 'add' or 'define' item")),
+            };
+            match kind {
+                "camera" => {
+                    if camera.is_some() {
+                        return Err(invalid(&path, "only one camera is allowed"));
+                    }
+                    camera = Some(camera_item(item, &path)?);
+                }
+                "light" => world.add_light(light_item(item, &path)?),
+                _ => world.objects.push(loader.shape(item, &path, None)?),
+            }
+        }
+
+        let camera = camera.ok_or_else(|| invalid("", "the scene has no camera"))?;
+        world.divide(BVH_THRESHOLD);
+        Ok(Self { world, camera })
+    }
+}
+
+fn camera_item(item: &Yaml, path: &str) -> Result<Camera> {
+    let keys = [
+        "add",
+        "width",
+        "height",
+        "field-of-view",
+        "from",
+        "to",
+        "up",
+    ];
+    let map = mapping(item, path, &keys)?;
+    let fov_path = format!("{}.field-of-view", path);
+    let field_of_view = number(required(map, "field-of-view", path)?, &fov_path)?;
+    if !(field_of_view > 0.0 && field_of_view < PI) {
+        return Err(invalid(
+            &fov_path,
+            format!(
+                "expected an angle between 0 and pi, found {}",
+                field_of_view
+            ),
+        ));
+    }
+    let mut camera = Camera::new(
+        size(required(map, "width", path)?, &format!("{}.width", path))?,
+        size(required(map, "height", path)?, &format!("{}.height", path))?,
+        field_of_view,
+    );
+    let from = triple(required(map, "from", path)?, &format!("{}.from", path))?;
+    let to = triple(required(map, "to", path)?, &format!("{}.to", path))?;
+    let up = triple(required(map, "up", path)?, &format!("{}.up", path))?;
+    let from = Tuple::point(from[0], from[1], from[2]);
+    let to = Tuple::point(to[0], to[1], to[2]);
+    let up = Tuple::vector(up[0], up[1], up[2]);
+    /* The view transform can't be built without a direction and a roll */
+    let forward = to - from;
+    if forward.magnitude() < EPSILON {
+        return Err(invalid(
+            &format!("{}.to", path),
+            "the camera must look away from its position",
+        ));
+    }
+    if forward.normal().cross(&up).magnitude() < EPSILON {
+        return Err(invalid(
+            &format!("{}.up", path),
+            "up must not be parallel to the view direction",
+        ));
+    }
+    camera.set_transform(Matrix::view_transform(&from, &to, &up));
+    Ok(camera)
+}
+
+fn light_item(item: &Yaml, path: &str) -> Result<PointLight> {
+    let map = mapping(item, path, &["add", "at", "intensity"])?;
+    let at = triple(required(map, "at", path)?, &format!("{}.at", path))?;
+    let intensity = color(
+        required(map, "intensity", path)?,
+        &format!("{}.intensity", path),
+    )?;
+    Ok(PointLight::new(
+        Tuple::point(at[0], at[1], at[2]),
+        intensity,
+    ))
+}
+
+struct Loader {
+    /* Defined values along with the path they were defined at */
+    defines: HashMap<String, (Yaml, String)>,
+    /* Names of the defines currently being resolved, to catch cycles */
+    resolving: RefCell<Vec<String>>,
+    dir: PathBuf,
+}
+
+impl Loader {
+    fn define(&mut self, item: &Yaml, path: &str) -> Result<()> {
+        let map = mapping(item, path, &["define", "extend", "value"])?;
+        let name = string(required(map, "define", path)?, &format!("{}.define", path))?;
+        let value_path = format!("{}.value", path);
+        let mut value = required(map, "value", path)?.clone();
+
+        if let Some(base) = get(map, "extend") {
+            let extend_path = format!("{}.extend", path);
+            let base = &self.lookup(string(base, &extend_path)?, &extend_path)?.0;
+            value = match (base, value) {
+                (Yaml::Mapping(base), Yaml::Mapping(own)) => {
+                    let mut merged = base.clone();
+                    for (key, v) in own {
+                        match merged.iter_mut().find(|(k, _)| *k == key) {
+                            Some(entry) => entry.1 = v,
+                            None => merged.push((key, v)),
+                        }
+                    }
+                    Yaml::Mapping(merged)
+                }
+                (Yaml::Sequence(base), Yaml::Sequence(own)) => {
+                    Yaml::Sequence(base.iter().cloned().chain(own).collect())
+                }
+                (base, own) => {
+                    return Err(invalid(
+                        &extend_path,
+                        format!(
+                            "cannot extend {} with {}",
+                            base.type_name(),
+                            own.type_name()
+                        ),
+                    ))
+                }
+            };
+        }
+
+        self.defines.insert(name.to_string(), (value, value_path));
+        Ok(())
+    }
+
+    fn lookup(&self, name: &str, path: &str) -> Result<&(Yaml, String)> {
+        self.defines
+            .get(name)
+            .ok_or_else(|| invalid(path, format!("unknown define '{}'", name)))
+    }
+
+    /* Builds a value from the define called `name` with `build` */
+    fn resolve<T>(
+        &self,
+        name: &str,
+        path: &str,
+        build: impl FnOnce(&Yaml, &str) -> Result<T>,
+    ) -> Result<T> {
+        if self.resolving.borrow().iter().any(|n| n == name) {
+            return Err(invalid(path, format!("define '{}' refers to itself", name)));
+        }
+        let (value, value_path) = self.lookup(name, path)?;
+        self.resolving.borrow_mut().push(name.to_string());
+        let result = build(value, value_path);
+        self.resolving.borrow_mut().pop();
+        result
+    }
+
+    /*
+     * Builds the shape described by `item`. Shapes without a material of
+     * their own use the one of the enclosing group, if any.
+     */
+    fn shape(
+        &self,
+        item: &Yaml,
+        path: &str,
+        inherited: Option<&Material>,
+    ) -> Result<Box<dyn Shape>> {
+        let add_path = format!("{}.add", path);
+        let kind = match item.get("add") {
+            Some(kind) => string(kind, &add_path)?,
+            None => return Err(invalid(path, "missing key 'add'")),
+        };
+        let extra: &[&str] = match kind {
+            "sphere" | "plane" | "cube" => &[],
+            "cylinder" | "cone" => &["min", "max", "closed"],
+            "group" => &["children"],
+            "triangle" => &["p1", "p2", "p3"],
+            "smooth-triangle" => &["p1", "p2", "p3", "n1", "n2", "n3"],
+            "csg" => &["operation", "left", "right"],
+            "obj" => &["file"],
+            _ => return Err(invalid(&add_path, format!("unknown shape '{}'", kind))),
+        };
+        let keys: Vec<&str> = ["add", "material", "transform"]
+            .iter()
+            .chain(extra)
+            .copied()
+            .collect();
+        let map = mapping(item, path, &keys)?;
+
+        let material = match get(map, "material") {
+            Some(m) => Some(self.material(m, &format!("{}.material", path))?),
+            None => inherited.cloned(),
+        };
+        let point = |key: &str| -> Result<Tuple> {
+            let p = triple(required(map, key, path)?, &format!("{}.{}", path, key))?;
+            Ok(Tuple::point(p[0], p[1], p[2]))
+        };
+        let vector = |key: &str| -> Result<Tuple> {
+            let v = triple(required(map, key, path)?, &format!("{}.{}", path, key))?;
+            Ok(Tuple::vector(v[0], v[1], v[2]))
+        };
+
+        let mut shape: Box<dyn Shape> = match kind {
+            "sphere" => Box::new(Sphere::new()),
+            "plane" => Box::new(Plane::new()),
+            "cube" => Box::new(Cube::new()),
+            "cylinder" | "cone" => {
+                let min = match get(map, "min") {
+                    Some(v) => number(v, &format!("{}.min", path))?,
+                    None => f64::NEG_INFINITY,
+                };
+                let max = match get(map, "max") {
+                    Some(v) => number(v, &format!("{}.max", path))?,
+                    None => f64::INFINITY,
+                };
+                let closed = match get(map, "closed") {
+                    Some(v) => boolean(v, &format!("{}.closed", path))?,
+                    None => false,
+                };
+                if kind == "cone" {
+                    Box::new(Cone::truncated(min, max, closed))
+                } else {
+                    Box::new(Cylinder::truncated(min, max, closed))
+                }
+            }
+            "group" => {
+                let mut group = Group::new();
+                let children_path = format!("{}.children", path);
+                if let Some(children) = get(map, "children") {
+                    for (i, child) in sequence(children, &children_path)?.iter().enumerate() {
+                        let child_path = format!("{}[{}]", children_path, i);
+                        group.add_boxed(self.shape(child, &child_path, material.as_ref())?);
+                    }
+                }
+                Box::new(group)
+            }
+            "triangle" => Box::new(Triangle::new(point("p1")?, point("p2")?, point("p3")?)),
+            "smooth-triangle" => Box::new(SmoothTriangle::new(
+                point("p1")?,
+                point("p2")?,
+                point("p3")?,
+                vector("n1")?,
+                vector("n2")?,
+                vector("n3")?,
+            )),
+            "csg" => {
+                let operation_path = format!("{}.operation", path);
+                let operation = match string(required(map, "operation", path)?, &operation_path)? {
+                    "union" => CsgOperation::Union,
+                    "intersection" => CsgOperation::Intersection,
+                    "difference" => CsgOperation::Difference,
+                    other => {
+                        return Err(invalid(
+                            &operation_path,
+                            format!("unknown operation '{}'", other),
+                        ))
+                    }
+                };
+                let left = self.shape(
+                    required(map, "left", path)?,
+                    &format!("{}.left", path),
+                    material.as_ref(),
+                )?;
+                let right = self.shape(
+                    required(map, "right", path)?,
+                    &format!("{}.right", path),
+                    material.as_ref(),
+                )?;
+                Box::new(Csg::from_boxed(operation, left, right))
+            }
+            "obj" => {
+                let file_path = format!("{}.file", path);
+                let file = string(required(map, "file", path)?, &file_path)?;
+                let full = self.dir.join(file);
+                let obj = ObjFile::load(&full.to_string_lossy())
+                    .map_err(|e| invalid(&file_path, format!("{}: {}", file, e)))?;
+                Box::new(obj.to_group())
+            }
+            _ => unreachable!(),
+        };
+
+        /*
+         * Children of groups and csg shapes were built with the material
+         * already, setting it again would replace the children's own.
+         */
+        if let Some(material) = material.filter(|_| kind != "group" && kind != "csg") {
+            shape.set_material(material);
+        }
+        if let Some(t) = get(map, "transform") {
+            shape.set_transform(self.transform(t, &format!("{}.transform", path))?);
+        }
+        Ok(shape)
+    }
+
+    fn material(&self, value: &Yaml, path: &str) -> Result<Material> {
+        if let Yaml::String(name) = value {
+            return self.resolve(name, path, |value, path| self.material(value, path));
+        }
+
+        let keys = [
+            "color",
+            "pattern",
+            "ambient",
+            "diffuse",
+            "specular",
+            "shininess",
+            "reflective",
+            "transparency",
+            "refractive-index",
+        ];
+        let map = mapping(value, path, &keys)?;
+        let mut material = Material::default();
+        for (key, v) in map {
+            let key_path = format!("{}.{}", path, key);
+            match key.as_str() {
+                "color" => material.color = color(v, &key_path)?,
+                "pattern" => material.pattern = Some(Arc::from(self.pattern(v, &key_path)?)),
+                "ambient" => material.ambient = number(v, &key_path)?,
+                "diffuse" => material.diffuse = number(v, &key_path)?,
+                "specular" => material.specular = number(v, &key_path)?,
+                "shininess" => material.shininess = number(v, &key_path)?,
+                "reflective" => material.reflective = number(v, &key_path)?,
+                "transparency" => material.transparency = number(v, &key_path)?,
+                _ => material.refractive_index = number(v, &key_path)?,
+            }
+        }
+        Ok(material)
+    }
+
+    fn pattern(&self, value: &Yaml, path: &str) -> Result<Box<dyn Pattern>> {
+        let type_path = format!("{}.type", path);
+        let all = ["type", "colors", "pattern", "scale", "color", "transform"];
+        let kind = string(
+            required(mapping(value, path, &all)?, "type", path)?,
+            &type_path,
+        )?;
+        let keys: &[&str] = match kind {
+            "stripes" | "gradient" | "rings" | "checkers" | "blend" => {
+                &["type", "colors", "transform"]
+            }
+            "perturbed" => &["type", "pattern", "scale", "transform"],
+            "solid" => &["type", "color", "transform"],
+            _ => return Err(invalid(&type_path, format!("unknown pattern '{}'", kind))),
+        };
+        let map = mapping(value, path, keys)?;
+
+        let mut pattern: Box<dyn Pattern> = match kind {
+            "perturbed" => {
+                let inner = self.pattern(
+                    required(map, "pattern", path)?,
+                    &format!("{}.pattern", path),
+                )?;
+                let scale = number(required(map, "scale", path)?, &format!("{}.scale", path))?;
+                Box::new(Perturbed::new(inner, scale))
+            }
+            "solid" => Box::new(Solid::new(color(
+                required(map, "color", path)?,
+                &format!("{}.color", path),
+            )?)),
+            _ => {
+                /* Each of the two colors may be a pattern of its own */
+                let colors_path = format!("{}.colors", path);
+                let colors = sequence(required(map, "colors", path)?, &colors_path)?;
+                if colors.len() != 2 {
+                    return Err(invalid(
+                        &colors_path,
+                        format!("expected 2 colors, found {}", colors.len()),
+                    ));
+                }
+                let sub = |i: usize| -> Result<Box<dyn Pattern>> {
+                    let sub_path = format!("{}[{}]", colors_path, i);
+                    match &colors[i] {
+                        Yaml::Mapping(_) => self.pattern(&colors[i], &sub_path),
+                        c => Ok(Box::new(Solid::new(color(c, &sub_path)?))),
+                    }
+                };
+                let (a, b) = (sub(0)?, sub(1)?);
+                match kind {
+                    "stripes" => Box::new(Stripe::from_patterns(a, b)),
+                    "gradient" => Box::new(Gradient::from_patterns(a, b)),
+                    "rings" => Box::new(Ring::from_patterns(a, b)),
+                    "checkers" => Box::new(Checkers::from_patterns(a, b)),
+                    _ => Box::new(Blend::new(a, b)),
+                }
+            }
+        };
+
+        if let Some(t) = get(map, "transform") {
+            pattern.set_transform(self.transform(t, &format!("{}.transform", path))?);
+        }
+        Ok(pattern)
+    }
+
+    /*
+     * A list of transformations applied in order, each either a list like
+     * `[ translate, 1, 2, 3 ]` or the name of a defined transformation list.
+     */
+    fn transform(&self, value: &Yaml, path: &str) -> Result<Matrix> {
+        let mut m = Matrix::identity();
+        for (i, step) in sequence(value, path)?.iter().enumerate() {
+            let step_path = format!("{}[{}]", path, i);
+            if let Yaml::String(name) = step {
+                m = self.resolve(name, &step_path, |value, path| self.transform(value, path))? * m;
+                continue;
+            }
+
+            let step = sequence(step, &step_path)?;
+            let Some((op, args)) = step.split_first() else {
+                return Err(invalid(&step_path, "expected a transformation"));
+            };
+            let op_path = format!("{}[0]", step_path);
+            let op = string(op, &op_path)?;
+            let expected = match op {
+                "translate" | "scale" => 3,
+                "rotate-x" | "rotate-y" | "rotate-z" => 1,
+                "shear" => 6,
+                _ => {
+                    return Err(invalid(
+                        &op_path,
+                        format!("unknown transformation '{}'", op),
+                    ))
+                }
+            };
+            if args.len() != expected {
+                return Err(invalid(
+                    &step_path,
+                    format!(
+                        "expected {} arguments for '{}', found {}",
+                        expected,
+                        op,
+                        args.len()
+                    ),
+                ));
+            }
+            let a = args
+                .iter()
+                .enumerate()
+                .map(|(j, v)| number(v, &format!("{}[{}]", step_path, j + 1)))
+                .collect::<Result<Vec<f64>>>()?;
+
+            m = match op {
+                "translate" => m.translate(a[0], a[1], a[2]),
+                "scale" => m.scale(a[0], a[1], a[2]),
+                "rotate-x" => m.rotate_x(a[0]),
+                "rotate-y" => m.rotate_y(a[0]),
+                "rotate-z" => m.rotate_z(a[0]),
+                _ => m.shear(a[0], a[1], a[2], a[3], a[4], a[5]),
+            };
+        }
+        /* Shapes and patterns need the inverse to map rays and points */
+        if !m.invertible() {
+            return Err(invalid(path, "transform is not invertible"));
+        }
+        Ok(m)
+    }
+}
+
+/* Checks that `value` is a mapping holding only the allowed keys */
+fn mapping<'a>(value: &'a Yaml, path: &str, allowed: &[&str]) -> Result<&'a [(String, Yaml)]> {
+    match value {
+        Yaml::Mapping(entries) => {
+            if let Some((key, _)) = entries.iter().find(|(k, _)| !allowed.contains(&k.as_str())) {
+                return Err(invalid(&format!("{}.{}", path, key), "unknown key"));
+            }
+            Ok(entries)
+        }
+        other => Err(invalid(
+            path,
+            format!("expected a mapping, found {}", other.type_name()),
+        )),
+    }
+}
+
+fn get<'a>(map: &'a [(String, Yaml)], key: &str) -> Option<&'a Yaml> {
+    map.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+}
+
+fn required<'a>(map: &'a [(String, Yaml)], key: &str, path: &str) -> Result<&'a Yaml> {
+    get(map, key).ok_or_else(|| invalid(path, format!("missing key '{}'", key)))
+}
+
+fn sequence<'a>(value: &'a Yaml, path: &str) -> Result<&'a [Yaml]> {
+    match value {
+        Yaml::Sequence(items) => Ok(items),
+        other => Err(invalid(
+            path,
+            format!("expected a sequence, found {}", other.type_name()),
+        )),
+    }
+}
+
+fn string<'a>(value: &'a Yaml, path: &str) -> Result<&'a str> {
+    match value {
+        Yaml::String(s) => Ok(s),
+        other => Err(invalid(
+            path,
+            format!("expected a string, found {}", other.type_name()),
+        )),
+    }
+}
+
+fn number(value: &Yaml, path: &str) -> Result<f64> {
+    match value {
+        Yaml::Number(n) => Ok(*n),
+        other => Err(invalid(
+            path,
+            format!("expected a number, found {}", other.type_name()),
+        )),
+    }
+}
+
+fn boolean(value: &Yaml, path: &str) -> Result<bool> {
+    match value {
+        Yaml::Bool(b) => Ok(*b),
+        other => Err(invalid(
+            path,
+            format!("expected a boolean, found {}", other.type_name()),
+        )),
+    }
+}
+
+fn size(value: &Yaml, path: &str) -> Result<usize> {
+    match number(value, path)? {
+        n if n >= 1.0 && n.fract() == 0.0 => Ok(n as usize),
+        n => Err(invalid(
+            path,
+            format!("expected a positive whole number, found {}", n),
+        )),
+    }
+}
+
+fn triple(value: &Yaml, path: &str) -> Result<[f64; 3]> {
+    let items = sequence(value, path)?;
+    if items.len() != 3 {
+        return Err(invalid(
+            path,
+            format!("expected 3 numbers, found {}", items.len()),
+        ));
+    }
+    let mut result = [0.0; 3];
+    for (i, item) in items.iter().enumerate() {
+        result[i] = number(item, &format!("{}[{}]", path, i))?;
+    }
+    Ok(result)
+}
+
+fn color(value: &Yaml, path: &str) -> Result<Tuple> {
+    let [r, g, b] = triple(value, path)?;
+    Ok(Tuple::color(r, g, b))
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use crate::math::{Matrix, Ray, Tuple};
+    use crate::parsers::{Scene, SceneError};
+
+    const CAMERA: &str = "\
+- add: camera
+  width: 100
+  height: 50
+  field-of-view: 0.785
+  from: [ 0, 1.5, -5 ]
+  to: [ 0, 1, 0 ]
+  up: [ 0, 1, 0 ]
+";
+
+    fn load(items: &str) -> Scene {
+        Scene::parse(&format!("{}{}", CAMERA, items)).unwrap()
+    }
+
+    fn error(items: &str) -> String {
+        match Scene::parse(&format!("{}{}", CAMERA, items)) {
+            Err(e @ SceneError::Invalid { .. }) => e.to_string(),
+            other => panic!("expected an invalid scene, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn camera_and_light() {
+        let scene = load("- add: light\n  at: [ -10, 10, -10 ]\n  intensity: [ 1, 1, 1 ]\n");
+        assert_eq!(scene.camera.hsize, 100);
+        assert_eq!(scene.camera.vsize, 50);
+        assert_eq!(scene.camera.field_of_view, 0.785);
+        assert_eq!(
+            scene.camera.transform(),
+            &Matrix::view_transform(
+                &Tuple::point(0.0, 1.5, -5.0),
+                &Tuple::point(0.0, 1.0, 0.0),
+                &Tuple::vector(0.0, 1.0, 0.0)
+            )
+        );
+        assert_eq!(scene.world.lights.len(), 1);
+        assert_eq!(
+            scene.world.lights[0].position,
+            Tuple::point(-10.0, 10.0, -10.0)
+        );
+    }
+
+    #[test]
+    fn shape_with_material_and_transform() {
+        let scene = load(
+            "\
+- add: sphere
+  material:
+    color: [ 1, 0.2, 1 ]
+    diffuse: 0.7
+    refractive-index: 1.5
+  transform:
+    - [ scale, 2, 2, 2 ]
+    - [ translate, 0, 1, 0 ]
+",
+        );
+        let s = &scene.world.objects[0];
+        assert_eq!(s.material().color, Tuple::color(1.0, 0.2, 1.0));
+        assert_eq!(s.material().diffuse, 0.7);
+        assert_eq!(s.material().refractive_index, 1.5);
+        assert_eq!(s.material().ambient, 0.1);
+        assert_eq!(
+            s.transform(),
+            &Matrix::identity()
+                .scale(2.0, 2.0, 2.0)
+                .translate(0.0, 1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn defines_and_extend() {
+        let scene = load(
+            "\
+- define: white-material
+  value:
+    color: [ 1, 1, 1 ]
+    diffuse: 0.7
+    reflective: 0.1
+
+- define: blue-material
+  extend: white-material
+  value:
+    color: [ 0.537, 0.831, 0.914 ]
+
+- define: standard-transform
+  value:
+    - [ translate, 1, -1, 1 ]
+    - [ scale, 0.5, 0.5, 0.5 ]
+
+- define: large-object
+  extend: standard-transform
+  value:
+    - [ scale, 3.5, 3.5, 3.5 ]
+
+- add: cube
+  material: blue-material
+  transform:
+    - large-object
+    - [ rotate-y, 1.5707963267948966 ]
+",
+        );
+        let c = &scene.world.objects[0];
+        assert_eq!(c.material().color, Tuple::color(0.537, 0.831, 0.914));
+        assert_eq!(c.material().diffuse, 0.7);
+        assert_eq!(c.material().reflective, 0.1);
+        let expected = Matrix::identity()
+            .translate(1.0, -1.0, 1.0)
+            .scale(0.5, 0.5, 0.5)
+            .scale(3.5, 3.5, 3.5)
+            .rotate_y(PI / 2.0);
+        assert_eq!(c.transform().round(5), expected.round(5));
+    }
+
+    #[test]
+    fn patterns() {
+        let scene = load(
+            "\
+- add: plane
+  material:
+    pattern:
+      type: checkers
+      colors:
+        - [ 1, 1, 1 ]
+        - type: stripes
+          colors: [ [ 1, 0, 0 ], [ 0, 0, 1 ] ]
+          transform:
+            - [ scale, 0.25, 0.25, 0.25 ]
+",
+        );
+        let p = scene.world.objects[0].material().pattern.clone().unwrap();
+        assert_eq!(
+            p.pattern_at(&Tuple::point(0.5, 0.0, 0.5)),
+            Tuple::color(1.0, 1.0, 1.0)
+        );
+        assert_eq!(
+            p.pattern_at(&Tuple::point(1.1, 0.0, 0.5)),
+            Tuple::color(1.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            p.pattern_at(&Tuple::point(1.3, 0.0, 0.5)),
+            Tuple::color(0.0, 0.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn truncated_cylinders_and_cones() {
+        let scene = load(
+            "\
+- add: cylinder
+  min: 0
+  max: 2
+  closed: true
+- add: cone
+  max: 0
+",
+        );
+        let r = Ray::new(Tuple::point(0.0, 3.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+        let xs = scene.world.objects[0].intersect(&r);
+        let ts: Vec<f64> = xs.iter().map(|i| i.t).collect();
+        assert_eq!(ts, vec![1.0, 3.0]);
+        assert_eq!(scene.world.objects[1].bounds().max.y(), 0.0);
+    }
+
+    #[test]
+    fn groups_pass_material_to_children() {
+        let scene = load(
+            "\
+- add: group
+  material:
+    color: [ 1, 0, 0 ]
+  transform:
+    - [ translate, 0, 0, 5 ]
+  children:
+    - add: sphere
+    - add: sphere
+      material:
+        color: [ 0, 1, 0 ]
+      transform:
+        - [ translate, 0, 3, 0 ]
+",
+        );
+        let w = &scene.world;
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = w.intersect_world(&r);
+        assert_eq!(xs[0].t, 9.0);
+        assert_eq!(xs[0].object.material().color, Tuple::color(1.0, 0.0, 0.0));
+        let r = Ray::new(Tuple::point(0.0, 3.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = w.intersect_world(&r);
+        assert_eq!(xs[0].object.material().color, Tuple::color(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn csg_and_triangles() {
+        let scene = load(
+            "\
+- add: csg
+  operation: difference
+  left:
+    add: cube
+  right:
+    add: sphere
+    transform:
+      - [ translate, 0, 0, -1 ]
+- add: triangle
+  p1: [ 5, 1, 10 ]
+  p2: [ 4, 0, 10 ]
+  p3: [ 6, 0, 10 ]
+",
+        );
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let ts: Vec<f64> = scene
+            .world
+            .intersect_world(&r)
+            .iter()
+            .map(|i| i.t)
+            .collect();
+        assert_eq!(ts, vec![5.0, 6.0]);
+        let r = Ray::new(Tuple::point(5.0, 0.5, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert_eq!(scene.world.intersect_world(&r)[0].t, 10.0);
+    }
+
+    #[test]
+    fn csg_children_keep_their_own_material() {
+        let scene = load(
+            "\
+- add: csg
+  operation: union
+  material:
+    color: [ 1, 0, 0 ]
+  left:
+    add: sphere
+  right:
+    add: sphere
+    material:
+      color: [ 0, 1, 0 ]
+    transform:
+      - [ translate, 0, 3, 0 ]
+",
+        );
+        let w = &scene.world;
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = w.intersect_world(&r);
+        assert_eq!(xs[0].object.material().color, Tuple::color(1.0, 0.0, 0.0));
+        let r = Ray::new(Tuple::point(0.0, 3.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = w.intersect_world(&r);
+        assert_eq!(xs[0].object.material().color, Tuple::color(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn missing_camera() {
+        let e = Scene::parse("- add: sphere\n").unwrap_err();
+        assert_eq!(e.to_string(), "the scene has no camera");
+    }
+
+    #[test]
+    fn unknown_key() {
+        assert_eq!(
+            error("- add: sphere\n  material:\n    colour: [ 1, 0, 0 ]\n"),
+            "[1].material.colour: unknown key"
+        );
+    }
+
+    #[test]
+    fn type_errors() {
+        assert_eq!(
+            error("- add: sphere\n  material:\n    color: [ 1, red, 0 ]\n"),
+            "[1].material.color[1]: expected a number, found a string"
+        );
+        assert_eq!(
+            error("- add: cylinder\n  closed: 1\n"),
+            "[1].closed: expected a boolean, found a number"
+        );
+        assert_eq!(
+            error("- add: cube\n  transform:\n    - [ scale, 1, 2 ]\n"),
+            "[1].transform[0]: expected 3 arguments for 'scale', found 2"
+        );
+    }
+
+    #[test]
+    fn degenerate_cameras() {
+        let camera = |fov: &str, from: &str, to: &str, up: &str| {
+            let input = format!(
+                "- add: camera\n  width: 10\n  height: 10\n  field-of-view: {}\n  from: {}\n  to: {}\n  up: {}\n",
+                fov, from, to, up
+            );
+            Scene::parse(&input).unwrap_err().to_string()
+        };
+        assert_eq!(
+            camera("1", "[ 1, 2, 3 ]", "[ 1, 2, 3 ]", "[ 0, 1, 0 ]"),
+            "[0].to: the camera must look away from its position"
+        );
+        assert_eq!(
+            camera("1", "[ 0, 0, 0 ]", "[ 0, 5, 0 ]", "[ 0, 1, 0 ]"),
+            "[0].up: up must not be parallel to the view direction"
+        );
+        assert_eq!(
+            camera("1", "[ 0, 0, -5 ]", "[ 0, 0, 0 ]", "[ 0, 0, 0 ]"),
+            "[0].up: up must not be parallel to the view direction"
+        );
+        for fov in ["0", "-1", "3.5"] {
+            assert_eq!(
+                camera(fov, "[ 0, 0, -5 ]", "[ 0, 0, 0 ]", "[ 0, 1, 0 ]"),
+                format!(
+                    "[0].field-of-view: expected an angle between 0 and pi, found {}",
+                    fov
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn singular_transforms() {
+        assert_eq!(
+            error("- add: cube\n  transform:\n    - [ scale, 0, 1, 1 ]\n"),
+            "[1].transform: transform is not invertible"
+        );
+        assert_eq!(
+            error(
+                "- add: cube\n  material:\n    pattern:\n      type: stripes\n      colors: [ [ 1, 1, 1 ], [ 0, 0, 0 ] ]\n      transform: [ [ scale, 1, 0, 1 ] ]\n"
+            ),
+            "[1].material.pattern.transform: transform is not invertible"
+        );
+    }
+
+    #[test]
+    fn defines_referring_to_themselves() {
+        assert_eq!(
+            error("- define: m\n  value: m\n- add: sphere\n  material: m\n"),
+            "[1].value: define 'm' refers to itself"
+        );
+        assert_eq!(
+            error("- define: t\n  value: [ t ]\n- add: sphere\n  transform: [ t ]\n"),
+            "[1].value[0]: define 't' refers to itself"
+        );
+        assert_eq!(
+            error(
+                "- define: a\n  value: b\n- define: b\n  value: a\n- add: sphere\n  material: a\n"
+            ),
+            "[2].value: define 'a' refers to itself"
+        );
+    }
+
+    #[test]
+    fn errors_inside_defines_point_at_define() {
+        assert_eq!(
+            error("- define: m\n  value:\n    diffuse: high\n- add: sphere\n  material: m\n"),
+            "[1].value.diffuse: expected a number, found a string"
+        );
+    }
+
+    #[test]
+    fn unknown_names() {
+        assert_eq!(error("- add: teapot\n"), "[1].add: unknown shape 'teapot'");
+        assert_eq!(
+            error("- add: sphere\n  material: shiny\n"),
+            "[1].material: unknown define 'shiny'"
+        );
+    }
+
+    #[test]
+    fn yaml_errors_have_line_numbers() {
+        let e = Scene::parse("- add: camera\n  width: [ 1, 2\n").unwrap_err();
+        assert!(matches!(e, SceneError::Yaml(_)));
+        assert_eq!(e.to_string(), "line 2: unclosed flow collection");
+    }
+}
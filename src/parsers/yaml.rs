@@ -0,0 +1,671 @@
+use std::{error, fmt};
+
+/*
+ * A parsed YAML document. Only the subset of YAML needed for scene files is
+ * supported: block mappings and sequences, flow sequences and mappings,
+ * plain and quoted scalars and comments.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum Yaml {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Sequence(Vec<Yaml>),
+    /* Entries are kept in the order they appear in the document */
+    Mapping(Vec<(String, Yaml)>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct YamlError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for YamlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl error::Error for YamlError {}
+
+#[allow(dead_code)]
+impl Yaml {
+    pub fn parse(input: &str) -> Result<Self, YamlError> {
+        let mut parser = Parser {
+            lines: split_lines(input)?,
+            pos: 0,
+        };
+        let Some(first) = parser.peek() else {
+            return Ok(Yaml::Null);
+        };
+        let value = parser.parse_block(first.indent)?;
+        match parser.peek() {
+            Some(line) => Err(line.error("unexpected indentation")),
+            None => Ok(value),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Yaml> {
+        match self {
+            Yaml::Mapping(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /* Describes the kind of value, for error messages */
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Yaml::Null => "null",
+            Yaml::Bool(_) => "a boolean",
+            Yaml::Number(_) => "a number",
+            Yaml::String(_) => "a string",
+            Yaml::Sequence(_) => "a sequence",
+            Yaml::Mapping(_) => "a mapping",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Line {
+    number: usize,
+    indent: usize,
+    text: String,
+}
+
+impl Line {
+    fn error(&self, message: &str) -> YamlError {
+        YamlError {
+            line: self.number,
+            message: message.to_string(),
+        }
+    }
+
+    fn is_sequence_item(&self) -> bool {
+        self.text == "-" || self.text.starts_with("- ")
+    }
+}
+
+/* Splits the input into indented lines, dropping comments and blank lines */
+fn split_lines(input: &str) -> Result<Vec<Line>, YamlError> {
+    let mut lines = vec![];
+    for (i, raw) in input.lines().enumerate() {
+        let content = strip_comment(raw).trim_end();
+        let text = content.trim_start_matches(' ');
+        if text.is_empty() || text == "---" || text == "..." {
+            continue;
+        }
+        if text.starts_with('\t') {
+            return Err(YamlError {
+                line: i + 1,
+                message: "tabs are not allowed for indentation".to_string(),
+            });
+        }
+        lines.push(Line {
+            number: i + 1,
+            indent: content.len() - text.len(),
+            text: text.to_string(),
+        });
+    }
+    Ok(lines)
+}
+
+/* A comment starts with a # that is not inside quotes and follows a space */
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' && previous.is_whitespace() => return &line[..i],
+            None => {}
+        }
+        previous = c;
+    }
+    line
+}
+
+/*
+ * Finds the `key: value` separator of a mapping line, ignoring colons inside
+ * quotes and flow collections.
+ */
+fn split_key(text: &str) -> Option<(String, &str)> {
+    if text.starts_with('[') || text.starts_with('{') {
+        return None;
+    }
+    let mut quote = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == ':' => {
+                let at_end = chars.peek().is_none_or(|(_, n)| *n == ' ');
+                if at_end {
+                    let key = text[..i].trim();
+                    let key = match Flow::new(key, 0).parse_document() {
+                        Ok(Yaml::String(s)) => s,
+                        _ => key.to_string(),
+                    };
+                    return Some((key, text[i + 1..].trim()));
+                }
+            }
+            None => {}
+        }
+    }
+    None
+}
+
+/* How many flow collections are left open at the end of the text */
+fn open_brackets(text: &str) -> i64 {
+    let mut quote = None;
+    let mut depth = 0;
+    for c in text.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '[' | '{' => depth += 1,
+                ']' | '}' => depth -= 1,
+                _ => {}
+            },
+        }
+    }
+    depth
+}
+
+struct Parser {
+    lines: Vec<Line>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Line> {
+        self.lines.get(self.pos)
+    }
+
+    fn parse_block(&mut self, indent: usize) -> Result<Yaml, YamlError> {
+        match self.peek() {
+            Some(line) if line.is_sequence_item() => self.parse_sequence(indent),
+            Some(line) if split_key(&line.text).is_some() => self.parse_mapping(indent),
+            Some(_) => self.parse_inline(),
+            None => Ok(Yaml::Null),
+        }
+    }
+
+    fn parse_sequence(&mut self, indent: usize) -> Result<Yaml, YamlError> {
+        let mut items = vec![];
+        while let Some(line) = self.peek() {
+            if line.indent > indent {
+                return Err(line.error("unexpected indentation"));
+            }
+            if line.indent < indent || !line.is_sequence_item() {
+                break;
+            }
+
+            let rest = line.text[1..].trim_start();
+            if rest.is_empty() {
+                self.pos += 1;
+                items.push(self.parse_nested(indent)?);
+            } else {
+                /*
+                 * The item starts on the same line as the dash, treat it as
+                 * if it was on a line of its own indented to where it starts.
+                 */
+                let offset = line.text.len() - rest.len();
+                let nested = Line {
+                    number: line.number,
+                    indent: indent + offset,
+                    text: rest.to_string(),
+                };
+                self.lines[self.pos] = nested;
+                items.push(self.parse_block(indent + offset)?);
+            }
+        }
+        Ok(Yaml::Sequence(items))
+    }
+
+    fn parse_mapping(&mut self, indent: usize) -> Result<Yaml, YamlError> {
+        let mut entries: Vec<(String, Yaml)> = vec![];
+        while let Some(line) = self.peek() {
+            if line.indent > indent {
+                return Err(line.error("unexpected indentation"));
+            }
+            if line.indent < indent {
+                break;
+            }
+            let line = line.clone();
+            let Some((key, rest)) = split_key(&line.text) else {
+                return Err(line.error("expected 'key: value'"));
+            };
+            if entries.iter().any(|(k, _)| *k == key) {
+                return Err(line.error(&format!("duplicate key '{}'", key)));
+            }
+
+            let value = if rest.is_empty() {
+                self.pos += 1;
+                match self.peek() {
+                    /* Sequences may sit at the same indentation as their key */
+                    Some(next) if next.indent == indent && next.is_sequence_item() => {
+                        self.parse_sequence(indent)?
+                    }
+                    _ => self.parse_nested(indent)?,
+                }
+            } else {
+                let rest = rest.to_string();
+                self.lines[self.pos].text = rest;
+                self.parse_inline()?
+            };
+            entries.push((key, value));
+        }
+        Ok(Yaml::Mapping(entries))
+    }
+
+    /* The value of a line ending in a colon or dash is on the lines below it */
+    fn parse_nested(&mut self, indent: usize) -> Result<Yaml, YamlError> {
+        match self.peek() {
+            Some(next) if next.indent > indent => {
+                let indent = next.indent;
+                self.parse_block(indent)
+            }
+            _ => Ok(Yaml::Null),
+        }
+    }
+
+    /*
+     * A scalar or flow collection, flow collections may continue over the
+     * following lines until every bracket is closed.
+     */
+    fn parse_inline(&mut self) -> Result<Yaml, YamlError> {
+        let first = self.lines[self.pos].clone();
+        let mut text = first.text.clone();
+        self.pos += 1;
+        while open_brackets(&text) > 0 {
+            let Some(next) = self.peek() else {
+                return Err(first.error("unclosed flow collection"));
+            };
+            text.push(' ');
+            text.push_str(&next.text);
+            self.pos += 1;
+        }
+        Flow::new(&text, first.number).parse_document()
+    }
+}
+
+/* Parses flow style values, like `[ 1, 2, 3 ]` or `{ a: 1 }`, and scalars */
+struct Flow {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Flow {
+    fn new(text: &str, line: usize) -> Self {
+        Self {
+            chars: text.chars().collect(),
+            pos: 0,
+            line,
+        }
+    }
+
+    fn error(&self, message: &str) -> YamlError {
+        YamlError {
+            line: self.line,
+            message: message.to_string(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn parse_document(&mut self) -> Result<Yaml, YamlError> {
+        self.skip_whitespace();
+        let value = match self.peek() {
+            Some('[') | Some('{') | Some('"') | Some('\'') => self.parse_value()?,
+            /* Outside of flow collections plain scalars may contain anything */
+            _ => {
+                let text: String = self.chars[self.pos..].iter().collect();
+                self.pos = self.chars.len();
+                resolve_plain(text.trim())
+            }
+        };
+        self.skip_whitespace();
+        if self.pos < self.chars.len() {
+            return Err(self.error("unexpected characters after value"));
+        }
+        Ok(value)
+    }
+
+    fn parse_value(&mut self) -> Result<Yaml, YamlError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('[') => self.parse_sequence(),
+            Some('{') => self.parse_mapping(),
+            Some('"') | Some('\'') => self.parse_quoted().map(Yaml::String),
+            Some(_) => Ok(resolve_plain(&self.parse_plain())),
+            None => Err(self.error("expected a value")),
+        }
+    }
+
+    fn parse_sequence(&mut self) -> Result<Yaml, YamlError> {
+        self.pos += 1;
+        let mut items = vec![];
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(']') {
+                self.pos += 1;
+                return Ok(Yaml::Sequence(items));
+            }
+            items.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {}
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_mapping(&mut self) -> Result<Yaml, YamlError> {
+        self.pos += 1;
+        let mut entries: Vec<(String, Yaml)> = vec![];
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some('}') {
+                self.pos += 1;
+                return Ok(Yaml::Mapping(entries));
+            }
+            let key = match self.peek() {
+                Some('"') | Some('\'') => self.parse_quoted()?,
+                _ => self.parse_plain(),
+            };
+            self.skip_whitespace();
+            if self.peek() != Some(':') {
+                return Err(self.error("expected ':' after key"));
+            }
+            self.pos += 1;
+            if entries.iter().any(|(k, _)| *k == key) {
+                return Err(self.error(&format!("duplicate key '{}'", key)));
+            }
+            let value = self.parse_value()?;
+            entries.push((key, value));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {}
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    /* A plain scalar inside a flow collection ends at an indicator */
+    fn parse_plain(&mut self) -> String {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if matches!(c, ',' | ']' | '}' | ':') {
+                break;
+            }
+            self.pos += 1;
+        }
+        self.chars[start..self.pos]
+            .iter()
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }
+
+    fn parse_quoted(&mut self) -> Result<String, YamlError> {
+        let quote = self.chars[self.pos];
+        self.pos += 1;
+        let mut s = String::new();
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                /* Single quoted strings escape a quote by doubling it */
+                '\'' if quote == '\'' && self.peek() == Some('\'') => {
+                    self.pos += 1;
+                    s.push('\'');
+                }
+                _ if c == quote => return Ok(s),
+                '\\' if quote == '"' => {
+                    let escaped = match self.peek() {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        _ => return Err(self.error("invalid escape sequence")),
+                    };
+                    self.pos += 1;
+                    s.push(escaped);
+                }
+                _ => s.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+}
+
+fn resolve_plain(text: &str) -> Yaml {
+    match text {
+        "" | "~" | "null" => Yaml::Null,
+        "true" => Yaml::Bool(true),
+        "false" => Yaml::Bool(false),
+        ".inf" | "+.inf" => Yaml::Number(f64::INFINITY),
+        "-.inf" => Yaml::Number(f64::NEG_INFINITY),
+        /* Rust also parses words like "inf", require a digit to rule them out */
+        _ => match text.parse() {
+            Ok(n) if text.chars().any(|c| c.is_ascii_digit()) => Yaml::Number(n),
+            _ => Yaml::String(text.to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parsers::{Yaml, YamlError};
+
+    fn string(s: &str) -> Yaml {
+        Yaml::String(s.to_string())
+    }
+
+    fn mapping(entries: &[(&str, Yaml)]) -> Yaml {
+        Yaml::Mapping(
+            entries
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect(),
+        )
+    }
+
+    fn numbers(ns: &[f64]) -> Yaml {
+        Yaml::Sequence(ns.iter().map(|n| Yaml::Number(*n)).collect())
+    }
+
+    #[test]
+    fn scalars() {
+        assert_eq!(Yaml::parse("42").unwrap(), Yaml::Number(42.0));
+        assert_eq!(Yaml::parse("-0.5").unwrap(), Yaml::Number(-0.5));
+        assert_eq!(Yaml::parse("true").unwrap(), Yaml::Bool(true));
+        assert_eq!(Yaml::parse("~").unwrap(), Yaml::Null);
+        assert_eq!(
+            Yaml::parse("-.inf").unwrap(),
+            Yaml::Number(f64::NEG_INFINITY)
+        );
+        assert_eq!(Yaml::parse("inf").unwrap(), string("inf"));
+        assert_eq!(Yaml::parse("hello world").unwrap(), string("hello world"));
+        assert_eq!(Yaml::parse("\"a: b # c\"").unwrap(), string("a: b # c"));
+        assert_eq!(Yaml::parse("'it''s'").unwrap(), string("it's"));
+        assert_eq!(Yaml::parse("").unwrap(), Yaml::Null);
+    }
+
+    #[test]
+    fn block_mapping() {
+        let y = Yaml::parse("a: 1\nb: two\nc:\n").unwrap();
+        assert_eq!(
+            y,
+            mapping(&[
+                ("a", Yaml::Number(1.0)),
+                ("b", string("two")),
+                ("c", Yaml::Null)
+            ])
+        );
+    }
+
+    #[test]
+    fn nested_mapping() {
+        let y = Yaml::parse("outer:\n  inner: 1\n  other: 2\nnext: 3\n").unwrap();
+        assert_eq!(
+            y,
+            mapping(&[
+                (
+                    "outer",
+                    mapping(&[("inner", Yaml::Number(1.0)), ("other", Yaml::Number(2.0))])
+                ),
+                ("next", Yaml::Number(3.0)),
+            ])
+        );
+    }
+
+    #[test]
+    fn block_sequence() {
+        let y = Yaml::parse("- 1\n- two\n-\n  - 3\n").unwrap();
+        assert_eq!(
+            y,
+            Yaml::Sequence(vec![Yaml::Number(1.0), string("two"), numbers(&[3.0])])
+        );
+    }
+
+    #[test]
+    fn sequence_of_mappings() {
+        let input = "\
+- add: camera
+  width: 100
+  from: [ -6, 6, -10 ]
+
+- add: light
+  at: [ 50, 100, -50 ]
+";
+        let y = Yaml::parse(input).unwrap();
+        assert_eq!(
+            y,
+            Yaml::Sequence(vec![
+                mapping(&[
+                    ("add", string("camera")),
+                    ("width", Yaml::Number(100.0)),
+                    ("from", numbers(&[-6.0, 6.0, -10.0])),
+                ]),
+                mapping(&[
+                    ("add", string("light")),
+                    ("at", numbers(&[50.0, 100.0, -50.0])),
+                ]),
+            ])
+        );
+    }
+
+    #[test]
+    fn sequence_at_key_indentation() {
+        let y = Yaml::parse("transform:\n- [ scale, 1, 2, 3 ]\n- name\nnext: 1\n").unwrap();
+        assert_eq!(
+            y,
+            mapping(&[
+                (
+                    "transform",
+                    Yaml::Sequence(vec![
+                        Yaml::Sequence(vec![
+                            string("scale"),
+                            Yaml::Number(1.0),
+                            Yaml::Number(2.0),
+                            Yaml::Number(3.0)
+                        ]),
+                        string("name"),
+                    ])
+                ),
+                ("next", Yaml::Number(1.0)),
+            ])
+        );
+    }
+
+    #[test]
+    fn flow_collections() {
+        let y = Yaml::parse("{ a: [1, [2, 3]], \"b c\": x, d: [] }").unwrap();
+        assert_eq!(
+            y,
+            mapping(&[
+                (
+                    "a",
+                    Yaml::Sequence(vec![Yaml::Number(1.0), numbers(&[2.0, 3.0])])
+                ),
+                ("b c", string("x")),
+                ("d", Yaml::Sequence(vec![])),
+            ])
+        );
+    }
+
+    #[test]
+    fn flow_over_several_lines() {
+        let y = Yaml::parse("colors: [\n  [1, 0, 0],\n  [0, 1, 0]\n]\nnext: 1\n").unwrap();
+        assert_eq!(
+            y.get("colors"),
+            Some(&Yaml::Sequence(vec![
+                numbers(&[1.0, 0.0, 0.0]),
+                numbers(&[0.0, 1.0, 0.0])
+            ]))
+        );
+        assert_eq!(y.get("next"), Some(&Yaml::Number(1.0)));
+    }
+
+    #[test]
+    fn comments() {
+        let y = Yaml::parse("# heading\n---\na: 1 # trailing\nb: \"#not\"\nc: x#y\n").unwrap();
+        assert_eq!(
+            y,
+            mapping(&[
+                ("a", Yaml::Number(1.0)),
+                ("b", string("#not")),
+                ("c", string("x#y"))
+            ])
+        );
+    }
+
+    #[test]
+    fn duplicate_key() {
+        assert_eq!(
+            Yaml::parse("a: 1\nb: 2\na: 3\n"),
+            Err(YamlError {
+                line: 3,
+                message: "duplicate key 'a'".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn bad_indentation() {
+        let e = Yaml::parse("a:\n  b: 1\n    c: 2\n").unwrap_err();
+        assert_eq!(e.line, 3);
+        assert_eq!(e.message, "unexpected indentation");
+    }
+
+    #[test]
+    fn unclosed_flow_sequence() {
+        let e = Yaml::parse("a: [1, 2\nb: 3\n").unwrap_err();
+        assert_eq!(e.line, 1);
+    }
+
+    #[test]
+    fn error_display() {
+        let e = Yaml::parse("a: [1, 2] 3\n").unwrap_err();
+        assert_eq!(e.to_string(), "line 1: unexpected characters after value");
+    }
+}
@@ -0,0 +1,74 @@
+use crate::math::Tuple;
+
+use super::{Pattern, PatternData};
+
+/*
+ * Averages the colors of two sub-patterns.
+ */
+#[derive(Debug)]
+pub struct Blend {
+    data: PatternData,
+    pub a: Box<dyn Pattern>,
+    pub b: Box<dyn Pattern>,
+}
+
+#[allow(dead_code)]
+impl Blend {
+    pub fn new(a: Box<dyn Pattern>, b: Box<dyn Pattern>) -> Self {
+        Self {
+            data: PatternData::default(),
+            a,
+            b,
+        }
+    }
+}
+
+impl Pattern for Blend {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn pattern_at(&self, point: &Tuple) -> Tuple {
+        (self.a.pattern_at_parent(point) + self.b.pattern_at_parent(point)) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use crate::math::{Matrix, Tuple};
+    use crate::patterns::{Blend, Pattern, Solid, Stripe};
+
+    #[test]
+    fn averages_sub_patterns() {
+        let p = Blend::new(
+            Box::new(Solid::new(Tuple::color(1.0, 0.0, 0.0))),
+            Box::new(Solid::new(Tuple::color(0.0, 0.0, 1.0))),
+        );
+        assert_eq!(
+            p.pattern_at(&Tuple::point(0.3, 0.2, 0.1)),
+            Tuple::color(0.5, 0.0, 0.5)
+        );
+    }
+
+    #[test]
+    fn crossed_stripes() {
+        let white = Tuple::color(1.0, 1.0, 1.0);
+        let black = Tuple::color(0.0, 0.0, 0.0);
+        let p = Blend::new(
+            Box::new(Stripe::new(white, black)),
+            Box::new(Stripe::new(white, black).with_transform(Matrix::rotation_y(PI / 2.0))),
+        );
+        assert_eq!(p.pattern_at(&Tuple::point(0.5, 0.0, -0.5)), white);
+        assert_eq!(p.pattern_at(&Tuple::point(1.5, 0.0, -1.5)), black);
+        assert_eq!(
+            p.pattern_at(&Tuple::point(0.5, 0.0, -1.5)),
+            Tuple::color(0.5, 0.5, 0.5)
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use crate::math::Tuple;
+
+use super::{Pattern, PatternData, Solid};
+
+/*
+ * Alternating cubes in all three dimensions.
+ */
+#[derive(Debug)]
+pub struct Checkers {
+    data: PatternData,
+    pub a: Box<dyn Pattern>,
+    pub b: Box<dyn Pattern>,
+}
+
+#[allow(dead_code)]
+impl Checkers {
+    pub fn new(a: Tuple, b: Tuple) -> Self {
+        Self::from_patterns(Box::new(Solid::new(a)), Box::new(Solid::new(b)))
+    }
+
+    pub fn from_patterns(a: Box<dyn Pattern>, b: Box<dyn Pattern>) -> Self {
+        Self {
+            data: PatternData::default(),
+            a,
+            b,
+        }
+    }
+}
+
+impl Pattern for Checkers {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn pattern_at(&self, point: &Tuple) -> Tuple {
+        let sum = point.x().floor() + point.y().floor() + point.z().floor();
+        if (sum as i64).rem_euclid(2) == 0 {
+            self.a.pattern_at_parent(point)
+        } else {
+            self.b.pattern_at_parent(point)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use crate::math::{Matrix, Tuple};
+    use crate::patterns::{Checkers, Pattern, Stripe};
+
+    fn white() -> Tuple {
+        Tuple::color(1.0, 1.0, 1.0)
+    }
+
+    fn black() -> Tuple {
+        Tuple::color(0.0, 0.0, 0.0)
+    }
+
+    #[test]
+    fn repeats_in_x() {
+        let p = Checkers::new(white(), black());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.99, 0.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(1.01, 0.0, 0.0)), black());
+    }
+
+    #[test]
+    fn repeats_in_y() {
+        let p = Checkers::new(white(), black());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.99, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 1.01, 0.0)), black());
+    }
+
+    #[test]
+    fn repeats_in_z() {
+        let p = Checkers::new(white(), black());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 0.99)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 1.01)), black());
+    }
+
+    #[test]
+    fn nested_stripes() {
+        let red = Tuple::color(1.0, 0.0, 0.0);
+        let green = Tuple::color(0.0, 1.0, 0.0);
+        let stripes = Stripe::new(white(), black()).with_transform(Matrix::scaling(0.25, 1.0, 1.0));
+        let turned = Stripe::new(red, green)
+            .with_transform(Matrix::identity().scale(0.25, 1.0, 1.0).rotate_y(PI / 2.0));
+        let p = Checkers::from_patterns(Box::new(stripes), Box::new(turned));
+
+        /* First square, stripes along x scaled to a quarter unit */
+        assert_eq!(p.pattern_at(&Tuple::point(0.1, 0.0, 0.1)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.3, 0.0, 0.1)), black());
+
+        /* Neighbouring square, stripes turned to run along z */
+        assert_eq!(p.pattern_at(&Tuple::point(1.1, 0.0, 0.1)), green);
+        assert_eq!(p.pattern_at(&Tuple::point(1.1, 0.0, 0.3)), red);
+    }
+}
@@ -0,0 +1,72 @@
+use crate::math::Tuple;
+
+use super::{Pattern, PatternData, Solid};
+
+/*
+ * Linearly blends from one color to the other along x, repeating every unit.
+ */
+#[derive(Debug)]
+pub struct Gradient {
+    data: PatternData,
+    pub a: Box<dyn Pattern>,
+    pub b: Box<dyn Pattern>,
+}
+
+#[allow(dead_code)]
+impl Gradient {
+    pub fn new(a: Tuple, b: Tuple) -> Self {
+        Self::from_patterns(Box::new(Solid::new(a)), Box::new(Solid::new(b)))
+    }
+
+    pub fn from_patterns(a: Box<dyn Pattern>, b: Box<dyn Pattern>) -> Self {
+        Self {
+            data: PatternData::default(),
+            a,
+            b,
+        }
+    }
+}
+
+impl Pattern for Gradient {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn pattern_at(&self, point: &Tuple) -> Tuple {
+        let a = self.a.pattern_at_parent(point);
+        let b = self.b.pattern_at_parent(point);
+        let fraction = point.x() - point.x().floor();
+        a + (b - a) * fraction
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::Tuple;
+    use crate::patterns::{Gradient, Pattern};
+
+    #[test]
+    fn linear_interpolation() {
+        let p = Gradient::new(Tuple::color(1.0, 1.0, 1.0), Tuple::color(0.0, 0.0, 0.0));
+        assert_eq!(
+            p.pattern_at(&Tuple::point(0.0, 0.0, 0.0)),
+            Tuple::color(1.0, 1.0, 1.0)
+        );
+        assert_eq!(
+            p.pattern_at(&Tuple::point(0.25, 0.0, 0.0)),
+            Tuple::color(0.75, 0.75, 0.75)
+        );
+        assert_eq!(
+            p.pattern_at(&Tuple::point(0.5, 0.0, 0.0)),
+            Tuple::color(0.5, 0.5, 0.5)
+        );
+        assert_eq!(
+            p.pattern_at(&Tuple::point(0.75, 0.0, 0.0)),
+            Tuple::color(0.25, 0.25, 0.25)
+        );
+    }
+}
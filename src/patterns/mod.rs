@@ -0,0 +1,19 @@
+mod blend;
+mod checkers;
+mod gradient;
+mod pattern;
+mod perturbed;
+mod ring;
+mod solid;
+mod stripe;
+
+pub use self::blend::Blend;
+pub use self::checkers::Checkers;
+pub use self::gradient::Gradient;
+#[cfg(test)]
+pub(crate) use self::pattern::test::TestPattern;
+pub use self::pattern::{Pattern, PatternData};
+pub use self::perturbed::Perturbed;
+pub use self::ring::Ring;
+pub use self::solid::Solid;
+pub use self::stripe::Stripe;
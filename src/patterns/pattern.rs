@@ -0,0 +1,127 @@
+use std::fmt::Debug;
+
+use crate::math::{Matrix, Tuple};
+use crate::shapes::Shape;
+
+/*
+ * State shared by every pattern, mirrors `ShapeData` for shapes.
+ */
+#[derive(Debug, Clone)]
+pub struct PatternData {
+    transform: Matrix,
+    inverse: Matrix,
+}
+
+impl Default for PatternData {
+    fn default() -> Self {
+        Self {
+            transform: Matrix::identity(),
+            inverse: Matrix::identity(),
+        }
+    }
+}
+
+#[allow(dead_code)]
+pub trait Pattern: Debug + Send + Sync {
+    fn data(&self) -> &PatternData;
+
+    fn data_mut(&mut self) -> &mut PatternData;
+
+    /* Color at a point given in pattern space */
+    fn pattern_at(&self, point: &Tuple) -> Tuple;
+
+    fn transform(&self) -> &Matrix {
+        &self.data().transform
+    }
+
+    fn inverse(&self) -> &Matrix {
+        &self.data().inverse
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        let data = self.data_mut();
+        data.inverse = transform.inverse();
+        data.transform = transform;
+    }
+
+    fn with_transform(mut self, transform: Matrix) -> Self
+    where
+        Self: Sized,
+    {
+        self.set_transform(transform);
+        self
+    }
+
+    /* Color at a point given in the space of the parent pattern */
+    fn pattern_at_parent(&self, point: &Tuple) -> Tuple {
+        self.pattern_at(&(self.inverse() * point))
+    }
+
+    fn pattern_at_shape(&self, object: &dyn Shape, world_point: &Tuple) -> Tuple {
+        let object_point = object.world_to_object(world_point);
+        self.pattern_at_parent(&object_point)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use crate::math::{Matrix, Tuple};
+    use crate::patterns::{Pattern, PatternData};
+    use crate::shapes::{Shape, Sphere};
+
+    /* Returns the pattern space point as a color */
+    #[derive(Debug, Default)]
+    pub(crate) struct TestPattern {
+        data: PatternData,
+    }
+
+    impl Pattern for TestPattern {
+        fn data(&self) -> &PatternData {
+            &self.data
+        }
+
+        fn data_mut(&mut self) -> &mut PatternData {
+            &mut self.data
+        }
+
+        fn pattern_at(&self, point: &Tuple) -> Tuple {
+            Tuple::color(point.x(), point.y(), point.z())
+        }
+    }
+
+    #[test]
+    fn default_transformation() {
+        let p = TestPattern::default();
+        assert_eq!(p.transform(), &Matrix::identity());
+    }
+
+    #[test]
+    fn assigning_transformation() {
+        let p = TestPattern::default().with_transform(Matrix::translation(1.0, 2.0, 3.0));
+        assert_eq!(p.transform(), &Matrix::translation(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn with_object_transformation() {
+        let shape = Sphere::new().with_transform(Matrix::scaling(2.0, 2.0, 2.0));
+        let p = TestPattern::default();
+        let c = p.pattern_at_shape(&shape, &Tuple::point(2.0, 3.0, 4.0));
+        assert_eq!(c, Tuple::color(1.0, 1.5, 2.0));
+    }
+
+    #[test]
+    fn with_pattern_transformation() {
+        let shape = Sphere::new();
+        let p = TestPattern::default().with_transform(Matrix::scaling(2.0, 2.0, 2.0));
+        let c = p.pattern_at_shape(&shape, &Tuple::point(2.0, 3.0, 4.0));
+        assert_eq!(c, Tuple::color(1.0, 1.5, 2.0));
+    }
+
+    #[test]
+    fn with_object_and_pattern_transformation() {
+        let shape = Sphere::new().with_transform(Matrix::scaling(2.0, 2.0, 2.0));
+        let p = TestPattern::default().with_transform(Matrix::translation(0.5, 1.0, 1.5));
+        let c = p.pattern_at_shape(&shape, &Tuple::point(2.5, 3.0, 3.5));
+        assert_eq!(c, Tuple::color(0.75, 0.5, 0.25));
+    }
+}
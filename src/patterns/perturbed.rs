@@ -0,0 +1,81 @@
+use crate::math::{perlin, Tuple};
+
+use super::{Pattern, PatternData};
+
+/*
+ * Jitters the lookup point of a sub-pattern with 3D Perlin noise, making
+ * otherwise perfect patterns look more organic.
+ */
+#[derive(Debug)]
+pub struct Perturbed {
+    data: PatternData,
+    pub pattern: Box<dyn Pattern>,
+    pub scale: f64,
+}
+
+#[allow(dead_code)]
+impl Perturbed {
+    pub fn new(pattern: Box<dyn Pattern>, scale: f64) -> Self {
+        Self {
+            data: PatternData::default(),
+            pattern,
+            scale,
+        }
+    }
+}
+
+impl Pattern for Perturbed {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn pattern_at(&self, point: &Tuple) -> Tuple {
+        let (x, y, z) = (point.x(), point.y(), point.z());
+        /* Offset the z lookups so each axis gets a different displacement */
+        let jitter = Tuple::vector(
+            perlin(x, y, z),
+            perlin(x, y, z + 1.0),
+            perlin(x, y, z + 2.0),
+        );
+        self.pattern
+            .pattern_at_parent(&(*point + jitter * self.scale))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::Tuple;
+    use crate::patterns::{Pattern, Perturbed, Solid, Stripe};
+
+    #[test]
+    fn zero_scale_is_unperturbed() {
+        let white = Tuple::color(1.0, 1.0, 1.0);
+        let black = Tuple::color(0.0, 0.0, 0.0);
+        let p = Perturbed::new(Box::new(Stripe::new(white, black)), 0.0);
+        assert_eq!(p.pattern_at(&Tuple::point(0.9, 0.3, 0.7)), white);
+        assert_eq!(p.pattern_at(&Tuple::point(1.1, 0.3, 0.7)), black);
+    }
+
+    #[test]
+    fn solid_is_unaffected() {
+        let c = Tuple::color(0.3, 0.6, 0.9);
+        let p = Perturbed::new(Box::new(Solid::new(c)), 1.0);
+        assert_eq!(p.pattern_at(&Tuple::point(0.37, 1.21, -0.5)), c);
+    }
+
+    #[test]
+    fn jitters_lookup_point() {
+        let white = Tuple::color(1.0, 1.0, 1.0);
+        let black = Tuple::color(0.0, 0.0, 0.0);
+        let point = Tuple::point(1.1, 0.3, 0.7);
+        /* The noise pushes x back by about 0.15, across the stripe edge at 1 */
+        let p = Perturbed::new(Box::new(Stripe::new(white, black)), 1.0);
+        assert_eq!(p.pattern_at(&point), white);
+        let p = Perturbed::new(Box::new(Stripe::new(white, black)), 0.0);
+        assert_eq!(p.pattern_at(&point), black);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::math::Tuple;
+
+use super::{Pattern, PatternData, Solid};
+
+/*
+ * Concentric rings around the y axis.
+ */
+#[derive(Debug)]
+pub struct Ring {
+    data: PatternData,
+    pub a: Box<dyn Pattern>,
+    pub b: Box<dyn Pattern>,
+}
+
+#[allow(dead_code)]
+impl Ring {
+    pub fn new(a: Tuple, b: Tuple) -> Self {
+        Self::from_patterns(Box::new(Solid::new(a)), Box::new(Solid::new(b)))
+    }
+
+    pub fn from_patterns(a: Box<dyn Pattern>, b: Box<dyn Pattern>) -> Self {
+        Self {
+            data: PatternData::default(),
+            a,
+            b,
+        }
+    }
+}
+
+impl Pattern for Ring {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn pattern_at(&self, point: &Tuple) -> Tuple {
+        let distance = (point.x().powi(2) + point.z().powi(2)).sqrt();
+        if (distance.floor() as i64).rem_euclid(2) == 0 {
+            self.a.pattern_at_parent(point)
+        } else {
+            self.b.pattern_at_parent(point)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::Tuple;
+    use crate::patterns::{Pattern, Ring};
+
+    #[test]
+    fn extends_in_x_and_z() {
+        let white = Tuple::color(1.0, 1.0, 1.0);
+        let black = Tuple::color(0.0, 0.0, 0.0);
+        let p = Ring::new(white, black);
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 0.0)), white);
+        assert_eq!(p.pattern_at(&Tuple::point(1.0, 0.0, 0.0)), black);
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 1.0)), black);
+        assert_eq!(p.pattern_at(&Tuple::point(0.708, 0.0, 0.708)), black);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::math::Tuple;
+
+use super::{Pattern, PatternData};
+
+/*
+ * A single flat color, mostly useful as a leaf in nested patterns.
+ */
+#[derive(Debug, Clone)]
+pub struct Solid {
+    data: PatternData,
+    pub color: Tuple,
+}
+
+#[allow(dead_code)]
+impl Solid {
+    pub fn new(color: Tuple) -> Self {
+        Self {
+            data: PatternData::default(),
+            color,
+        }
+    }
+}
+
+impl Pattern for Solid {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn pattern_at(&self, _point: &Tuple) -> Tuple {
+        self.color
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::{Matrix, Tuple};
+    use crate::patterns::{Pattern, Solid};
+
+    #[test]
+    fn constant_everywhere() {
+        let c = Tuple::color(0.2, 0.4, 0.6);
+        let p = Solid::new(c).with_transform(Matrix::scaling(2.0, 2.0, 2.0));
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 0.0)), c);
+        assert_eq!(p.pattern_at_parent(&Tuple::point(-3.5, 1.2, 8.0)), c);
+    }
+}
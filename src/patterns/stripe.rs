@@ -0,0 +1,120 @@
+use crate::math::Tuple;
+
+use super::{Pattern, PatternData, Solid};
+
+/*
+ * Alternates between two colors along x.
+ */
+#[derive(Debug)]
+pub struct Stripe {
+    data: PatternData,
+    pub a: Box<dyn Pattern>,
+    pub b: Box<dyn Pattern>,
+}
+
+#[allow(dead_code)]
+impl Stripe {
+    pub fn new(a: Tuple, b: Tuple) -> Self {
+        Self::from_patterns(Box::new(Solid::new(a)), Box::new(Solid::new(b)))
+    }
+
+    pub fn from_patterns(a: Box<dyn Pattern>, b: Box<dyn Pattern>) -> Self {
+        Self {
+            data: PatternData::default(),
+            a,
+            b,
+        }
+    }
+}
+
+impl Pattern for Stripe {
+    fn data(&self) -> &PatternData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut PatternData {
+        &mut self.data
+    }
+
+    fn pattern_at(&self, point: &Tuple) -> Tuple {
+        if (point.x().floor() as i64).rem_euclid(2) == 0 {
+            self.a.pattern_at_parent(point)
+        } else {
+            self.b.pattern_at_parent(point)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::{Matrix, Tuple};
+    use crate::patterns::{Pattern, Stripe};
+    use crate::shapes::{Shape, Sphere};
+
+    fn white() -> Tuple {
+        Tuple::color(1.0, 1.0, 1.0)
+    }
+
+    fn black() -> Tuple {
+        Tuple::color(0.0, 0.0, 0.0)
+    }
+
+    #[test]
+    fn creating_stripe() {
+        let p = Stripe::new(white(), black());
+        let origin = Tuple::point(0.0, 0.0, 0.0);
+        assert_eq!(p.a.pattern_at(&origin), white());
+        assert_eq!(p.b.pattern_at(&origin), black());
+    }
+
+    #[test]
+    fn constant_in_y() {
+        let p = Stripe::new(white(), black());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 1.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 2.0, 0.0)), white());
+    }
+
+    #[test]
+    fn constant_in_z() {
+        let p = Stripe::new(white(), black());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 1.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 2.0)), white());
+    }
+
+    #[test]
+    fn alternates_in_x() {
+        let p = Stripe::new(white(), black());
+        assert_eq!(p.pattern_at(&Tuple::point(0.0, 0.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(0.9, 0.0, 0.0)), white());
+        assert_eq!(p.pattern_at(&Tuple::point(1.0, 0.0, 0.0)), black());
+        assert_eq!(p.pattern_at(&Tuple::point(-0.1, 0.0, 0.0)), black());
+        assert_eq!(p.pattern_at(&Tuple::point(-1.0, 0.0, 0.0)), black());
+        assert_eq!(p.pattern_at(&Tuple::point(-1.1, 0.0, 0.0)), white());
+    }
+
+    #[test]
+    fn stripes_with_object_transformation() {
+        let object = Sphere::new().with_transform(Matrix::scaling(2.0, 2.0, 2.0));
+        let p = Stripe::new(white(), black());
+        let c = p.pattern_at_shape(&object, &Tuple::point(1.5, 0.0, 0.0));
+        assert_eq!(c, white());
+    }
+
+    #[test]
+    fn stripes_with_pattern_transformation() {
+        let object = Sphere::new();
+        let p = Stripe::new(white(), black()).with_transform(Matrix::scaling(2.0, 2.0, 2.0));
+        let c = p.pattern_at_shape(&object, &Tuple::point(1.5, 0.0, 0.0));
+        assert_eq!(c, white());
+    }
+
+    #[test]
+    fn stripes_with_both_transformations() {
+        let object = Sphere::new().with_transform(Matrix::scaling(2.0, 2.0, 2.0));
+        let p = Stripe::new(white(), black()).with_transform(Matrix::translation(0.5, 0.0, 0.0));
+        let c = p.pattern_at_shape(&object, &Tuple::point(2.5, 0.0, 0.0));
+        assert_eq!(c, white());
+    }
+}
@@ -0,0 +1,248 @@
+use std::thread;
+
+use crate::graphics::Canvas;
+use crate::math::{Matrix, Ray, Tuple};
+
+use super::World;
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct Camera {
+    pub hsize: usize,
+    pub vsize: usize,
+    pub field_of_view: f64,
+    transform: Matrix,
+    inverse: Matrix,
+    half_width: f64,
+    half_height: f64,
+    pixel_size: f64,
+}
+
+#[allow(dead_code)]
+impl Camera {
+    pub fn new(hsize: usize, vsize: usize, field_of_view: f64) -> Self {
+        let half_view = (field_of_view / 2.0).tan();
+        let aspect = hsize as f64 / vsize as f64;
+        let (half_width, half_height) = if aspect >= 1.0 {
+            (half_view, half_view / aspect)
+        } else {
+            (half_view * aspect, half_view)
+        };
+
+        Self {
+            hsize,
+            vsize,
+            field_of_view,
+            transform: Matrix::identity(),
+            inverse: Matrix::identity(),
+            half_width,
+            half_height,
+            pixel_size: (half_width * 2.0) / hsize as f64,
+        }
+    }
+
+    pub fn transform(&self) -> &Matrix {
+        &self.transform
+    }
+
+    pub fn set_transform(&mut self, transform: Matrix) {
+        self.inverse = transform.inverse();
+        self.transform = transform;
+    }
+
+    pub fn pixel_size(&self) -> f64 {
+        self.pixel_size
+    }
+
+    pub fn ray_for_pixel(&self, px: usize, py: usize) -> Ray {
+        /* Aim for the pixel's center */
+        self.ray_for_point(px as f64 + 0.5, py as f64 + 0.5)
+    }
+
+    /* Ray through a point on the canvas, measured in pixels from the top left */
+    pub fn ray_for_point(&self, x: f64, y: f64) -> Ray {
+        let xoffset = x * self.pixel_size;
+        let yoffset = y * self.pixel_size;
+
+        /* The camera looks toward -z, so +x is to the left */
+        let world_x = self.half_width - xoffset;
+        let world_y = self.half_height - yoffset;
+
+        let pixel = &self.inverse * Tuple::point(world_x, world_y, -1.0);
+        let origin = &self.inverse * Tuple::point(0.0, 0.0, 0.0);
+        let direction = (pixel - origin).normal();
+
+        Ray::new(origin, direction)
+    }
+
+    /*
+     * Color of a pixel averaged over a grid of `samples` by `samples` rays
+     * spread evenly across it.
+     */
+    pub fn color_at_pixel(&self, world: &World, px: usize, py: usize, samples: usize) -> Tuple {
+        let samples = samples.max(1);
+        let step = 1.0 / samples as f64;
+        let mut color = Tuple::color(0.0, 0.0, 0.0);
+        for sy in 0..samples {
+            for sx in 0..samples {
+                let x = px as f64 + (sx as f64 + 0.5) * step;
+                let y = py as f64 + (sy as f64 + 0.5) * step;
+                color = color + world.color_at(&self.ray_for_point(x, y));
+            }
+        }
+        color * step.powi(2)
+    }
+
+    pub fn render(&self, world: &World) -> Canvas {
+        self.render_with(world, 1, 1)
+    }
+
+    /*
+     * Renders with `samples` squared rays per pixel. Rows are handed out to
+     * the threads in turn so each gets a similar share of the scene.
+     */
+    pub fn render_with(&self, world: &World, samples: usize, threads: usize) -> Canvas {
+        let threads = threads.clamp(1, self.vsize.max(1));
+        let rows: Vec<(usize, Vec<Tuple>)> = thread::scope(|s| {
+            let workers: Vec<_> = (0..threads)
+                .map(|first| {
+                    s.spawn(move || {
+                        (first..self.vsize)
+                            .step_by(threads)
+                            .map(|y| {
+                                let row = (0..self.hsize)
+                                    .map(|x| self.color_at_pixel(world, x, y, samples))
+                                    .collect();
+                                (y, row)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|w| w.join().expect("render thread panicked"))
+                .collect()
+        });
+
+        let mut image = Canvas::new(self.hsize, self.vsize);
+        for (y, row) in rows {
+            image.matrix[y] = row;
+        }
+        image
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+    use crate::math::{epsilon_eq as feq, round, Matrix, Tuple};
+    use crate::scene::{Camera, World};
+
+    #[test]
+    fn constructing_camera() {
+        let c = Camera::new(160, 120, PI / 2.0);
+        assert_eq!(c.hsize, 160);
+        assert_eq!(c.vsize, 120);
+        assert!(feq(c.field_of_view, PI / 2.0));
+        assert_eq!(c.transform(), &Matrix::identity());
+    }
+
+    #[test]
+    fn pixel_size_horizontal_canvas() {
+        let c = Camera::new(200, 125, PI / 2.0);
+        assert_eq!(round(c.pixel_size(), 5), 0.01);
+    }
+
+    #[test]
+    fn pixel_size_vertical_canvas() {
+        let c = Camera::new(125, 200, PI / 2.0);
+        assert_eq!(round(c.pixel_size(), 5), 0.01);
+    }
+
+    #[test]
+    fn ray_through_center() {
+        let c = Camera::new(201, 101, PI / 2.0);
+        let r = c.ray_for_pixel(100, 50);
+        assert_eq!(r.origin, Tuple::point(0.0, 0.0, 0.0));
+        assert_eq!(r.direction.round(5), Tuple::vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn ray_through_corner() {
+        let c = Camera::new(201, 101, PI / 2.0);
+        let r = c.ray_for_pixel(0, 0);
+        assert_eq!(r.origin, Tuple::point(0.0, 0.0, 0.0));
+        assert_eq!(
+            r.direction.round(5),
+            Tuple::vector(0.66519, 0.33259, -0.66851)
+        );
+    }
+
+    #[test]
+    fn ray_when_camera_transformed() {
+        let mut c = Camera::new(201, 101, PI / 2.0);
+        c.set_transform(Matrix::translation(0.0, -2.0, 5.0).rotate_y(PI / 4.0));
+        let r = c.ray_for_pixel(100, 50);
+        assert_eq!(r.origin.round(5), Tuple::point(0.0, 2.0, -5.0));
+        assert_eq!(
+            r.direction.round(5),
+            Tuple::vector(FRAC_1_SQRT_2, 0.0, -FRAC_1_SQRT_2).round(5)
+        );
+    }
+
+    #[test]
+    fn rendering_world() {
+        let w = World::default_world();
+        let mut c = Camera::new(11, 11, PI / 2.0);
+        let from = Tuple::point(0.0, 0.0, -5.0);
+        let to = Tuple::point(0.0, 0.0, 0.0);
+        let up = Tuple::vector(0.0, 1.0, 0.0);
+        c.set_transform(Matrix::view_transform(&from, &to, &up));
+        let image = c.render(&w);
+        assert_eq!(
+            image.get_pixel(5, 5).round(5),
+            Tuple::color(0.38066, 0.47583, 0.2855)
+        );
+    }
+
+    fn default_world_camera(hsize: usize, vsize: usize) -> Camera {
+        let mut c = Camera::new(hsize, vsize, PI / 2.0);
+        let from = Tuple::point(0.0, 0.0, -5.0);
+        let to = Tuple::point(0.0, 0.0, 0.0);
+        let up = Tuple::vector(0.0, 1.0, 0.0);
+        c.set_transform(Matrix::view_transform(&from, &to, &up));
+        c
+    }
+
+    #[test]
+    fn rendering_with_threads() {
+        let w = World::default_world();
+        let c = default_world_camera(11, 7);
+        let single = c.render(&w);
+        let threaded = c.render_with(&w, 1, 4);
+        assert_eq!(threaded.matrix, single.matrix);
+    }
+
+    #[test]
+    fn more_threads_than_rows() {
+        let w = World::default_world();
+        let c = default_world_camera(3, 2);
+        assert_eq!(c.render_with(&w, 1, 16).matrix, c.render(&w).matrix);
+    }
+
+    #[test]
+    fn supersampling_averages_rays() {
+        let w = World::default_world();
+        let c = default_world_camera(11, 11);
+        let sum = [(5.25, 5.25), (5.75, 5.25), (5.25, 5.75), (5.75, 5.75)]
+            .iter()
+            .fold(Tuple::color(0.0, 0.0, 0.0), |sum, (x, y)| {
+                sum + w.color_at(&c.ray_for_point(*x, *y))
+            });
+        let color = c.color_at_pixel(&w, 5, 5, 2);
+        assert_eq!(color.round(5), (sum * 0.25).round(5));
+        assert_ne!(color.round(5), c.color_at_pixel(&w, 5, 5, 1).round(5));
+    }
+}
@@ -0,0 +1,263 @@
+use crate::math::{Ray, Tuple, EPSILON};
+use crate::shapes::Shape;
+
+use super::{Intersection, Intersections};
+
+/*
+ * Precomputed state of an intersection, used when shading the hit.
+ */
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct Computations<'a> {
+    pub t: f64,
+    pub object: &'a dyn Shape,
+    pub point: Tuple,
+    pub over_point: Tuple,
+    pub under_point: Tuple,
+    pub eyev: Tuple,
+    pub normalv: Tuple,
+    pub reflectv: Tuple,
+    pub inside: bool,
+    /* Refractive indices on either side of the surface */
+    pub n1: f64,
+    pub n2: f64,
+}
+
+#[allow(dead_code)]
+impl<'a> Intersection<'a> {
+    pub fn prepare_computations(&self, ray: &Ray, xs: &Intersections<'a>) -> Computations<'a> {
+        let point = ray.position(self.t);
+        let eyev = -ray.direction;
+        let mut normalv = self.object.normal_at_hit(&point, self);
+        let inside = normalv.dot(&eyev) < 0.0;
+        if inside {
+            normalv = -normalv;
+        }
+
+        let (n1, n2) = self.refractive_indices(xs);
+
+        Computations {
+            t: self.t,
+            object: self.object,
+            point,
+            /* Nudge the point off the surface to avoid self intersection */
+            over_point: point + normalv * EPSILON,
+            under_point: point - normalv * EPSILON,
+            eyev,
+            normalv,
+            reflectv: ray.direction.reflect(&normalv),
+            inside,
+            n1,
+            n2,
+        }
+    }
+
+    /*
+     * Walks the intersections up to this one, keeping track of which objects
+     * the ray is currently inside of, to find the refractive index of the
+     * material being exited and the one being entered.
+     */
+    fn refractive_indices(&self, xs: &Intersections<'a>) -> (f64, f64) {
+        let mut containers: Vec<&dyn Shape> = vec![];
+        let mut n1 = 1.0;
+        let mut n2 = 1.0;
+
+        for i in xs.iter() {
+            let is_hit = i == self;
+            if is_hit {
+                n1 = containers
+                    .last()
+                    .map_or(1.0, |o| o.material().refractive_index);
+            }
+
+            match containers
+                .iter()
+                .position(|o| std::ptr::addr_eq(*o, i.object))
+            {
+                Some(pos) => {
+                    containers.remove(pos);
+                }
+                None => containers.push(i.object),
+            }
+
+            if is_hit {
+                n2 = containers
+                    .last()
+                    .map_or(1.0, |o| o.material().refractive_index);
+                break;
+            }
+        }
+
+        (n1, n2)
+    }
+}
+
+#[allow(dead_code)]
+impl Computations<'_> {
+    /*
+     * Schlick's approximation of the Fresnel equations, the fraction of light
+     * that is reflected rather than refracted.
+     */
+    pub fn schlick(&self) -> f64 {
+        let mut cos = self.eyev.dot(&self.normalv);
+        if self.n1 > self.n2 {
+            let n = self.n1 / self.n2;
+            let sin2_t = n.powi(2) * (1.0 - cos.powi(2));
+            if sin2_t > 1.0 {
+                /* Total internal reflection */
+                return 1.0;
+            }
+            cos = (1.0 - sin2_t).sqrt();
+        }
+
+        let r0 = ((self.n1 - self.n2) / (self.n1 + self.n2)).powi(2);
+        r0 + (1.0 - r0) * (1.0 - cos).powi(5)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::graphics::Material;
+    use crate::math::{round, Matrix, Ray, Tuple, EPSILON};
+    use crate::scene::{Intersection, Intersections};
+    use crate::shapes::{Plane, Shape, Sphere};
+
+    #[test]
+    fn precomputing_state() {
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let shape = Sphere::new();
+        let i = Intersection::new(4.0, &shape);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(comps.t, i.t);
+        assert!(std::ptr::addr_eq(comps.object, &shape));
+        assert_eq!(comps.point, Tuple::point(0.0, 0.0, -1.0));
+        assert_eq!(comps.eyev, Tuple::vector(0.0, 0.0, -1.0));
+        assert_eq!(comps.normalv, Tuple::vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn hit_on_outside() {
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let shape = Sphere::new();
+        let i = Intersection::new(4.0, &shape);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert!(!comps.inside);
+    }
+
+    #[test]
+    fn hit_on_inside() {
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+        let shape = Sphere::new();
+        let i = Intersection::new(1.0, &shape);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(comps.point, Tuple::point(0.0, 0.0, 1.0));
+        assert_eq!(comps.eyev, Tuple::vector(0.0, 0.0, -1.0));
+        assert!(comps.inside);
+        assert_eq!(comps.normalv, Tuple::vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn hit_offsets_point() {
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let shape = Sphere::new().with_transform(Matrix::translation(0.0, 0.0, 1.0));
+        let i = Intersection::new(5.0, &shape);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert!(comps.over_point.z() < -EPSILON / 2.0);
+        assert!(comps.point.z() > comps.over_point.z());
+    }
+
+    #[test]
+    fn precomputing_reflection_vector() {
+        let shape = Plane::new();
+        let v = 2f64.sqrt() / 2.0;
+        let r = Ray::new(Tuple::point(0.0, 1.0, -1.0), Tuple::vector(0.0, -v, v));
+        let i = Intersection::new(2f64.sqrt(), &shape);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(comps.reflectv.round(5), Tuple::vector(0.0, v, v).round(5));
+    }
+
+    #[test]
+    fn finding_n1_and_n2() {
+        let a = Sphere::glass().with_transform(Matrix::scaling(2.0, 2.0, 2.0));
+        let b = Sphere::glass()
+            .with_transform(Matrix::translation(0.0, 0.0, -0.25))
+            .with_material(Material {
+                transparency: 1.0,
+                refractive_index: 2.0,
+                ..Default::default()
+            });
+        let c = Sphere::glass()
+            .with_transform(Matrix::translation(0.0, 0.0, 0.25))
+            .with_material(Material {
+                transparency: 1.0,
+                refractive_index: 2.5,
+                ..Default::default()
+            });
+        let r = Ray::new(Tuple::point(0.0, 0.0, -4.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = Intersections::new(vec![
+            Intersection::new(2.0, &a),
+            Intersection::new(2.75, &b),
+            Intersection::new(3.25, &c),
+            Intersection::new(4.75, &b),
+            Intersection::new(5.25, &c),
+            Intersection::new(6.0, &a),
+        ]);
+        let expected = [
+            (1.0, 1.5),
+            (1.5, 2.0),
+            (2.0, 2.5),
+            (2.5, 2.5),
+            (2.5, 1.5),
+            (1.5, 1.0),
+        ];
+        for (i, (n1, n2)) in expected.iter().enumerate() {
+            let comps = xs[i].prepare_computations(&r, &xs);
+            assert_eq!(comps.n1, *n1);
+            assert_eq!(comps.n2, *n2);
+        }
+    }
+
+    #[test]
+    fn under_point_below_surface() {
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let shape = Sphere::glass().with_transform(Matrix::translation(0.0, 0.0, 1.0));
+        let i = Intersection::new(5.0, &shape);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert!(comps.under_point.z() > EPSILON / 2.0);
+        assert!(comps.point.z() < comps.under_point.z());
+    }
+
+    #[test]
+    fn schlick_total_internal_reflection() {
+        let shape = Sphere::glass();
+        let v = 2f64.sqrt() / 2.0;
+        let r = Ray::new(Tuple::point(0.0, 0.0, v), Tuple::vector(0.0, 1.0, 0.0));
+        let xs = Intersections::new(vec![
+            Intersection::new(-v, &shape),
+            Intersection::new(v, &shape),
+        ]);
+        let comps = xs[1].prepare_computations(&r, &xs);
+        assert_eq!(comps.schlick(), 1.0);
+    }
+
+    #[test]
+    fn schlick_perpendicular() {
+        let shape = Sphere::glass();
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0));
+        let xs = Intersections::new(vec![
+            Intersection::new(-1.0, &shape),
+            Intersection::new(1.0, &shape),
+        ]);
+        let comps = xs[1].prepare_computations(&r, &xs);
+        assert_eq!(round(comps.schlick(), 5), 0.04);
+    }
+
+    #[test]
+    fn schlick_small_angle_n2_greater() {
+        let shape = Sphere::glass();
+        let r = Ray::new(Tuple::point(0.0, 0.99, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = Intersections::new(vec![Intersection::new(1.8589, &shape)]);
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert_eq!(round(comps.schlick(), 5), 0.48873);
+    }
+}
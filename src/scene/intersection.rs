@@ -0,0 +1,165 @@
+use std::ops::Deref;
+
+use crate::math::epsilon_eq as feq;
+use crate::shapes::Shape;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Intersection<'a> {
+    pub t: f64,
+    pub object: &'a dyn Shape,
+    /* Where on a triangle the hit landed, relative to its second and third point */
+    pub u: f64,
+    pub v: f64,
+}
+
+#[allow(dead_code)]
+impl<'a> Intersection<'a> {
+    pub fn new(t: f64, object: &'a dyn Shape) -> Self {
+        Self::with_uv(t, object, 0.0, 0.0)
+    }
+
+    pub fn with_uv(t: f64, object: &'a dyn Shape, u: f64, v: f64) -> Self {
+        Self { t, object, u, v }
+    }
+}
+
+impl PartialEq for Intersection<'_> {
+    fn eq(&self, o: &Self) -> bool {
+        feq(self.t, o.t) && std::ptr::addr_eq(self.object, o.object)
+    }
+}
+
+/*
+ * A list of intersections that is always kept sorted by t, lowest first.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct Intersections<'a> {
+    list: Vec<Intersection<'a>>,
+}
+
+#[allow(dead_code)]
+impl<'a> Intersections<'a> {
+    pub fn new(mut list: Vec<Intersection<'a>>) -> Self {
+        list.sort_by(|a, b| a.t.total_cmp(&b.t));
+        Self { list }
+    }
+
+    pub fn empty() -> Self {
+        Self { list: vec![] }
+    }
+
+    pub fn merge(&mut self, other: Intersections<'a>) {
+        self.list.extend(other.list);
+        self.list.sort_by(|a, b| a.t.total_cmp(&b.t));
+    }
+
+    pub fn hit(&self) -> Option<&Intersection<'a>> {
+        self.list.iter().find(|i| i.t >= 0.0)
+    }
+}
+
+impl<'a> Deref for Intersections<'a> {
+    type Target = Vec<Intersection<'a>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.list
+    }
+}
+
+impl<'a> From<Vec<Intersection<'a>>> for Intersections<'a> {
+    fn from(list: Vec<Intersection<'a>>) -> Self {
+        Self::new(list)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::Tuple;
+    use crate::scene::{Intersection, Intersections};
+    use crate::shapes::{Sphere, Triangle};
+
+    #[test]
+    fn intersection() {
+        let s = Sphere::new();
+        let i = Intersection::new(3.5, &s);
+        assert_eq!(i.t, 3.5);
+        assert!(std::ptr::addr_eq(i.object, &s));
+    }
+
+    #[test]
+    fn intersection_with_uv() {
+        let s = Triangle::new(
+            Tuple::point(0.0, 1.0, 0.0),
+            Tuple::point(-1.0, 0.0, 0.0),
+            Tuple::point(1.0, 0.0, 0.0),
+        );
+        let i = Intersection::with_uv(3.5, &s, 0.2, 0.4);
+        assert_eq!(i.u, 0.2);
+        assert_eq!(i.v, 0.4);
+    }
+
+    #[test]
+    fn aggregating_intersections() {
+        let s = Sphere::new();
+        let i1 = Intersection::new(1.0, &s);
+        let i2 = Intersection::new(2.0, &s);
+        let xs = Intersections::new(vec![i1, i2]);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 1.0);
+        assert_eq!(xs[1].t, 2.0);
+    }
+
+    #[test]
+    fn hit_all_positive() {
+        let s = Sphere::new();
+        let i1 = Intersection::new(1.0, &s);
+        let i2 = Intersection::new(2.0, &s);
+        let xs = Intersections::new(vec![i2, i1]);
+        assert_eq!(xs.hit(), Some(&i1));
+    }
+
+    #[test]
+    fn hit_some_negative() {
+        let s = Sphere::new();
+        let i1 = Intersection::new(-1.0, &s);
+        let i2 = Intersection::new(1.0, &s);
+        let xs = Intersections::new(vec![i2, i1]);
+        assert_eq!(xs.hit(), Some(&i2));
+    }
+
+    #[test]
+    fn hit_all_negative() {
+        let s = Sphere::new();
+        let i1 = Intersection::new(-2.0, &s);
+        let i2 = Intersection::new(-1.0, &s);
+        let xs = Intersections::new(vec![i2, i1]);
+        assert_eq!(xs.hit(), None);
+    }
+
+    #[test]
+    fn hit_lowest_nonnegative() {
+        let s = Sphere::new();
+        let i1 = Intersection::new(5.0, &s);
+        let i2 = Intersection::new(7.0, &s);
+        let i3 = Intersection::new(-3.0, &s);
+        let i4 = Intersection::new(2.0, &s);
+        let xs = Intersections::new(vec![i1, i2, i3, i4]);
+        assert_eq!(xs.hit(), Some(&i4));
+    }
+
+    #[test]
+    fn merging_intersections() {
+        let a = Sphere::new();
+        let b = Sphere::new();
+        let mut xs =
+            Intersections::new(vec![Intersection::new(4.0, &a), Intersection::new(6.0, &a)]);
+        xs.merge(Intersections::new(vec![
+            Intersection::new(-1.0, &b),
+            Intersection::new(5.0, &b),
+        ]));
+        let ts: Vec<f64> = xs.iter().map(|i| i.t).collect();
+        assert_eq!(ts, vec![-1.0, 4.0, 5.0, 6.0]);
+        assert!(std::ptr::addr_eq(xs[1].object, &a));
+        assert!(std::ptr::addr_eq(xs[2].object, &b));
+    }
+}
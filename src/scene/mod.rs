@@ -0,0 +1,9 @@
+mod camera;
+mod computations;
+mod intersection;
+mod world;
+
+pub use self::camera::Camera;
+pub use self::computations::Computations;
+pub use self::intersection::{Intersection, Intersections};
+pub use self::world::World;
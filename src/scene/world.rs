@@ -0,0 +1,603 @@
+use crate::graphics::{lighting, PointLight};
+use crate::math::{Ray, Tuple};
+use crate::shapes::Shape;
+
+use super::{Computations, Intersections};
+
+/* How many times a ray may bounce before giving up */
+pub const DEFAULT_MAX_DEPTH: usize = 5;
+
+#[derive(Debug)]
+pub struct World {
+    pub objects: Vec<Box<dyn Shape>>,
+    pub lights: Vec<PointLight>,
+    pub max_depth: usize,
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self {
+            objects: vec![],
+            lights: vec![],
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl World {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /*
+     * The default world from the book, two concentric spheres lit by a
+     * single white light.
+     */
+    #[cfg(test)]
+    pub fn default_world() -> Self {
+        use crate::graphics::Material;
+        use crate::math::Matrix;
+        use crate::shapes::Sphere;
+
+        let s1 = Sphere::new().with_material(Material {
+            color: Tuple::color(0.8, 1.0, 0.6),
+            diffuse: 0.7,
+            specular: 0.2,
+            ..Default::default()
+        });
+        let s2 = Sphere::new().with_transform(Matrix::scaling(0.5, 0.5, 0.5));
+
+        let mut w = Self::new();
+        w.add_object(s1);
+        w.add_object(s2);
+        w.add_light(PointLight::new(
+            Tuple::point(-10.0, 10.0, -10.0),
+            Tuple::color(1.0, 1.0, 1.0),
+        ));
+        w
+    }
+
+    pub fn add_object<S: Shape + 'static>(&mut self, object: S) {
+        self.objects.push(Box::new(object));
+    }
+
+    pub fn add_light(&mut self, light: PointLight) {
+        self.lights.push(light);
+    }
+
+    /* Builds bounding volume hierarchies for every group in the world */
+    pub fn divide(&mut self, threshold: usize) {
+        for object in self.objects.iter_mut() {
+            object.divide(threshold);
+        }
+    }
+
+    pub fn intersect_world(&self, ray: &Ray) -> Intersections<'_> {
+        let mut xs = Intersections::empty();
+        for object in &self.objects {
+            xs.merge(object.intersect(ray));
+        }
+        xs
+    }
+
+    pub fn shade_hit(&self, comps: &Computations, remaining: usize) -> Tuple {
+        let surface = self
+            .lights
+            .iter()
+            .map(|light| {
+                lighting(
+                    comps.object.material(),
+                    comps.object,
+                    light,
+                    &comps.over_point,
+                    &comps.eyev,
+                    &comps.normalv,
+                    self.is_shadowed(light, &comps.over_point),
+                )
+            })
+            .fold(Tuple::color(0.0, 0.0, 0.0), |acc, c| acc + c);
+
+        let reflected = self.reflected_color(comps, remaining);
+        let refracted = self.refracted_color(comps, remaining);
+
+        let material = comps.object.material();
+        if material.reflective > 0.0 && material.transparency > 0.0 {
+            let reflectance = comps.schlick();
+            surface + reflected * reflectance + refracted * (1.0 - reflectance)
+        } else {
+            surface + reflected + refracted
+        }
+    }
+
+    pub fn reflected_color(&self, comps: &Computations, remaining: usize) -> Tuple {
+        let reflective = comps.object.material().reflective;
+        if remaining == 0 || reflective == 0.0 {
+            return Tuple::color(0.0, 0.0, 0.0);
+        }
+
+        let reflect_ray = Ray::new(comps.over_point, comps.reflectv);
+        self.color_at_depth(&reflect_ray, remaining - 1) * reflective
+    }
+
+    pub fn refracted_color(&self, comps: &Computations, remaining: usize) -> Tuple {
+        let transparency = comps.object.material().transparency;
+        if remaining == 0 || transparency == 0.0 {
+            return Tuple::color(0.0, 0.0, 0.0);
+        }
+
+        /* Snell's law, find the angle of the refracted ray */
+        let n_ratio = comps.n1 / comps.n2;
+        let cos_i = comps.eyev.dot(&comps.normalv);
+        let sin2_t = n_ratio.powi(2) * (1.0 - cos_i.powi(2));
+        if sin2_t > 1.0 {
+            /* Total internal reflection */
+            return Tuple::color(0.0, 0.0, 0.0);
+        }
+
+        let cos_t = (1.0 - sin2_t).sqrt();
+        let direction = comps.normalv * (n_ratio * cos_i - cos_t) - comps.eyev * n_ratio;
+        let refract_ray = Ray::new(comps.under_point, direction);
+        self.color_at_depth(&refract_ray, remaining - 1) * transparency
+    }
+
+    pub fn is_shadowed(&self, light: &PointLight, point: &Tuple) -> bool {
+        let v = light.position - *point;
+        let distance = v.magnitude();
+        let ray = Ray::new(*point, v.normal());
+        let xs = self.intersect_world(&ray);
+        matches!(xs.hit(), Some(hit) if hit.t < distance)
+    }
+
+    pub fn color_at(&self, ray: &Ray) -> Tuple {
+        self.color_at_depth(ray, self.max_depth)
+    }
+
+    /*
+     * Like `color_at` but with an explicit number of bounces left, which
+     * keeps mutually reflective surfaces from recursing forever.
+     */
+    pub fn color_at_depth(&self, ray: &Ray, remaining: usize) -> Tuple {
+        let xs = self.intersect_world(ray);
+        match xs.hit() {
+            Some(hit) => self.shade_hit(&hit.prepare_computations(ray, &xs), remaining),
+            None => Tuple::color(0.0, 0.0, 0.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::graphics::{Material, PointLight};
+    use crate::math::{Matrix, Ray, Tuple, EPSILON};
+    use crate::patterns::TestPattern;
+    use crate::scene::world::DEFAULT_MAX_DEPTH;
+    use crate::scene::{Intersection, Intersections, World};
+    use crate::shapes::{Plane, Shape, Sphere};
+
+    #[test]
+    fn creating_world() {
+        let w = World::new();
+        assert!(w.objects.is_empty());
+        assert!(w.lights.is_empty());
+    }
+
+    #[test]
+    fn default_world() {
+        let w = World::default_world();
+        let light = PointLight::new(
+            Tuple::point(-10.0, 10.0, -10.0),
+            Tuple::color(1.0, 1.0, 1.0),
+        );
+        assert_eq!(w.lights, vec![light]);
+        assert_eq!(w.objects[0].material().color, Tuple::color(0.8, 1.0, 0.6));
+        assert_eq!(w.objects[1].transform(), &Matrix::scaling(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn intersect_world() {
+        let w = World::default_world();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = w.intersect_world(&r);
+        let ts: Vec<f64> = xs.iter().map(|i| i.t).collect();
+        assert_eq!(ts, vec![4.0, 4.5, 5.5, 6.0]);
+    }
+
+    #[test]
+    fn shading_intersection() {
+        let w = World::default_world();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let i = Intersection::new(4.0, &*w.objects[0]);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        let c = w.shade_hit(&comps, DEFAULT_MAX_DEPTH);
+        assert_eq!(c.round(5), Tuple::color(0.38066, 0.47583, 0.2855));
+    }
+
+    #[test]
+    fn shading_intersection_from_inside() {
+        let mut w = World::default_world();
+        w.lights = vec![PointLight::new(
+            Tuple::point(0.0, 0.25, 0.0),
+            Tuple::color(1.0, 1.0, 1.0),
+        )];
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+        let i = Intersection::new(0.5, &*w.objects[1]);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        let c = w.shade_hit(&comps, DEFAULT_MAX_DEPTH);
+        assert_eq!(c.round(5), Tuple::color(0.90498, 0.90498, 0.90498));
+    }
+
+    #[test]
+    fn shading_with_multiple_lights() {
+        let mut w = World::default_world();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let single = w.color_at(&r);
+        w.add_light(w.lights[0].clone());
+        let i = Intersection::new(4.0, &*w.objects[0]);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        let c = w.shade_hit(&comps, DEFAULT_MAX_DEPTH);
+        assert_eq!(c.round(5), (single * 2.0).round(5));
+    }
+
+    #[test]
+    fn color_when_ray_misses() {
+        let w = World::default_world();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 1.0, 0.0));
+        assert_eq!(w.color_at(&r), Tuple::color(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn color_when_ray_hits() {
+        let w = World::default_world();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert_eq!(
+            w.color_at(&r).round(5),
+            Tuple::color(0.38066, 0.47583, 0.2855)
+        );
+    }
+
+    #[test]
+    fn color_with_intersection_behind_ray() {
+        let mut w = World::default_world();
+        for object in w.objects.iter_mut() {
+            object.set_material(Material {
+                ambient: 1.0,
+                ..object.material().clone()
+            });
+        }
+        let inner_color = w.objects[1].material().color;
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.75), Tuple::vector(0.0, 0.0, -1.0));
+        assert_eq!(w.color_at(&r), inner_color);
+    }
+
+    #[test]
+    fn adding_objects() {
+        let mut w = World::new();
+        w.add_object(Sphere::new());
+        assert_eq!(w.objects.len(), 1);
+    }
+
+    #[test]
+    fn no_shadow_when_nothing_collinear() {
+        let w = World::default_world();
+        let p = Tuple::point(0.0, 10.0, 0.0);
+        assert!(!w.is_shadowed(&w.lights[0], &p));
+    }
+
+    #[test]
+    fn shadow_when_object_between_point_and_light() {
+        let w = World::default_world();
+        let p = Tuple::point(10.0, -10.0, 10.0);
+        assert!(w.is_shadowed(&w.lights[0], &p));
+    }
+
+    #[test]
+    fn no_shadow_when_object_behind_light() {
+        let w = World::default_world();
+        let p = Tuple::point(-20.0, 20.0, -20.0);
+        assert!(!w.is_shadowed(&w.lights[0], &p));
+    }
+
+    #[test]
+    fn no_shadow_when_object_behind_point() {
+        let w = World::default_world();
+        let p = Tuple::point(-2.0, 2.0, -2.0);
+        assert!(!w.is_shadowed(&w.lights[0], &p));
+    }
+
+    #[test]
+    fn shade_hit_in_shadow() {
+        let mut w = World::new();
+        w.add_light(PointLight::new(
+            Tuple::point(0.0, 0.0, -10.0),
+            Tuple::color(1.0, 1.0, 1.0),
+        ));
+        w.add_object(Sphere::new());
+        w.add_object(Sphere::new().with_transform(Matrix::translation(0.0, 0.0, 10.0)));
+        let r = Ray::new(Tuple::point(0.0, 0.0, 5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let i = Intersection::new(4.0, &*w.objects[1]);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(
+            w.shade_hit(&comps, DEFAULT_MAX_DEPTH),
+            Tuple::color(0.1, 0.1, 0.1)
+        );
+    }
+
+    #[test]
+    fn shadows_per_light() {
+        let mut w = World::new();
+        w.add_object(Sphere::new());
+        w.add_light(PointLight::new(
+            Tuple::point(0.0, 0.0, -10.0),
+            Tuple::color(1.0, 1.0, 1.0),
+        ));
+        w.add_light(PointLight::new(
+            Tuple::point(0.0, 0.0, 10.0),
+            Tuple::color(1.0, 1.0, 1.0),
+        ));
+        let p = Tuple::point(0.0, 0.0, -1.0 - EPSILON);
+        assert!(!w.is_shadowed(&w.lights[0], &p));
+        assert!(w.is_shadowed(&w.lights[1], &p));
+    }
+
+    #[test]
+    fn intersect_heterogeneous_objects() {
+        let mut w = World::new();
+        w.add_object(Sphere::new());
+        w.add_object(Plane::new().with_transform(Matrix::translation(0.0, -1.0, 0.0)));
+        let r = Ray::new(Tuple::point(0.0, 5.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+        let xs = w.intersect_world(&r);
+        let ts: Vec<f64> = xs.iter().map(|i| i.t).collect();
+        assert_eq!(ts, vec![4.0, 6.0, 6.0]);
+        assert!(std::ptr::addr_eq(xs[0].object, &*w.objects[0]));
+    }
+
+    #[test]
+    fn reflected_color_nonreflective() {
+        let mut w = World::default_world();
+        let m = Material {
+            ambient: 1.0,
+            ..w.objects[1].material().clone()
+        };
+        w.objects[1].set_material(m);
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+        let i = Intersection::new(1.0, &*w.objects[1]);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(
+            w.reflected_color(&comps, DEFAULT_MAX_DEPTH),
+            Tuple::color(0.0, 0.0, 0.0)
+        );
+    }
+
+    fn world_with_mirror_plane() -> World {
+        let mut w = World::default_world();
+        w.add_object(
+            Plane::new()
+                .with_material(Material {
+                    reflective: 0.5,
+                    ..Default::default()
+                })
+                .with_transform(Matrix::translation(0.0, -1.0, 0.0)),
+        );
+        w
+    }
+
+    #[test]
+    fn reflected_color_reflective() {
+        let w = world_with_mirror_plane();
+        let v = 2f64.sqrt() / 2.0;
+        let r = Ray::new(Tuple::point(0.0, 0.0, -3.0), Tuple::vector(0.0, -v, v));
+        let i = Intersection::new(2f64.sqrt(), &*w.objects[2]);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(
+            w.reflected_color(&comps, DEFAULT_MAX_DEPTH).round(4),
+            Tuple::color(0.1903, 0.2379, 0.1427)
+        );
+    }
+
+    #[test]
+    fn shade_hit_reflective() {
+        let w = world_with_mirror_plane();
+        let v = 2f64.sqrt() / 2.0;
+        let r = Ray::new(Tuple::point(0.0, 0.0, -3.0), Tuple::vector(0.0, -v, v));
+        let i = Intersection::new(2f64.sqrt(), &*w.objects[2]);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(
+            w.shade_hit(&comps, DEFAULT_MAX_DEPTH).round(4),
+            Tuple::color(0.8768, 0.9243, 0.8292)
+        );
+    }
+
+    #[test]
+    fn mutually_reflective_surfaces() {
+        let mut w = World::new();
+        w.add_light(PointLight::new(
+            Tuple::point(0.0, 0.0, 0.0),
+            Tuple::color(1.0, 1.0, 1.0),
+        ));
+        let mirror = Material {
+            reflective: 1.0,
+            ..Default::default()
+        };
+        w.add_object(
+            Plane::new()
+                .with_material(mirror.clone())
+                .with_transform(Matrix::translation(0.0, -1.0, 0.0)),
+        );
+        w.add_object(
+            Plane::new()
+                .with_material(mirror)
+                .with_transform(Matrix::translation(0.0, 1.0, 0.0)),
+        );
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0));
+        /* Only has to terminate */
+        w.color_at(&r);
+    }
+
+    #[test]
+    fn reflected_color_at_max_depth() {
+        let w = world_with_mirror_plane();
+        let v = 2f64.sqrt() / 2.0;
+        let r = Ray::new(Tuple::point(0.0, 0.0, -3.0), Tuple::vector(0.0, -v, v));
+        let i = Intersection::new(2f64.sqrt(), &*w.objects[2]);
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(w.reflected_color(&comps, 0), Tuple::color(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn configurable_max_depth() {
+        let mut w = world_with_mirror_plane();
+        let v = 2f64.sqrt() / 2.0;
+        let r = Ray::new(Tuple::point(0.0, 0.0, -3.0), Tuple::vector(0.0, -v, v));
+        let reflected = w.color_at(&r);
+        w.max_depth = 0;
+        let flat = w.color_at(&r);
+        assert_eq!(
+            (reflected - flat).round(4),
+            Tuple::color(0.1903, 0.2379, 0.1427)
+        );
+    }
+
+    #[test]
+    fn refracted_color_opaque() {
+        let w = World::default_world();
+        let shape = &*w.objects[0];
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = Intersections::new(vec![
+            Intersection::new(4.0, shape),
+            Intersection::new(6.0, shape),
+        ]);
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert_eq!(w.refracted_color(&comps, 5), Tuple::color(0.0, 0.0, 0.0));
+    }
+
+    fn glassify(w: &mut World, index: usize) {
+        let m = Material {
+            transparency: 1.0,
+            refractive_index: 1.5,
+            ..w.objects[index].material().clone()
+        };
+        w.objects[index].set_material(m);
+    }
+
+    #[test]
+    fn refracted_color_at_max_depth() {
+        let mut w = World::default_world();
+        glassify(&mut w, 0);
+        let shape = &*w.objects[0];
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = Intersections::new(vec![
+            Intersection::new(4.0, shape),
+            Intersection::new(6.0, shape),
+        ]);
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert_eq!(w.refracted_color(&comps, 0), Tuple::color(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn refracted_color_total_internal_reflection() {
+        let mut w = World::default_world();
+        glassify(&mut w, 0);
+        let shape = &*w.objects[0];
+        let v = 2f64.sqrt() / 2.0;
+        let r = Ray::new(Tuple::point(0.0, 0.0, v), Tuple::vector(0.0, 1.0, 0.0));
+        let xs = Intersections::new(vec![
+            Intersection::new(-v, shape),
+            Intersection::new(v, shape),
+        ]);
+        /* Inside the sphere, so look at the second intersection */
+        let comps = xs[1].prepare_computations(&r, &xs);
+        assert_eq!(w.refracted_color(&comps, 5), Tuple::color(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn refracted_color_with_refracted_ray() {
+        let mut w = World::default_world();
+        let m = Material {
+            ambient: 1.0,
+            pattern: Some(Arc::new(TestPattern::default())),
+            ..w.objects[0].material().clone()
+        };
+        w.objects[0].set_material(m);
+        glassify(&mut w, 1);
+        let a = &*w.objects[0];
+        let b = &*w.objects[1];
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.1), Tuple::vector(0.0, 1.0, 0.0));
+        let xs = Intersections::new(vec![
+            Intersection::new(-0.9899, a),
+            Intersection::new(-0.4899, b),
+            Intersection::new(0.4899, b),
+            Intersection::new(0.9899, a),
+        ]);
+        let comps = xs[2].prepare_computations(&r, &xs);
+        assert_eq!(
+            w.refracted_color(&comps, 5).round(4),
+            Tuple::color(0.0, 0.9989, 0.0472)
+        );
+    }
+
+    #[test]
+    fn shade_hit_transparent() {
+        let mut w = World::default_world();
+        w.add_object(
+            Plane::new()
+                .with_transform(Matrix::translation(0.0, -1.0, 0.0))
+                .with_material(Material {
+                    transparency: 0.5,
+                    refractive_index: 1.5,
+                    ..Default::default()
+                }),
+        );
+        w.add_object(
+            Sphere::new()
+                .with_transform(Matrix::translation(0.0, -3.5, -0.5))
+                .with_material(Material {
+                    color: Tuple::color(1.0, 0.0, 0.0),
+                    ambient: 0.5,
+                    ..Default::default()
+                }),
+        );
+        let v = 2f64.sqrt() / 2.0;
+        let r = Ray::new(Tuple::point(0.0, 0.0, -3.0), Tuple::vector(0.0, -v, v));
+        let xs = Intersections::new(vec![Intersection::new(2f64.sqrt(), &*w.objects[2])]);
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert_eq!(
+            w.shade_hit(&comps, 5).round(4),
+            Tuple::color(0.9364, 0.6864, 0.6864)
+        );
+    }
+
+    #[test]
+    fn shade_hit_schlick() {
+        let mut w = World::default_world();
+        w.add_object(
+            Plane::new()
+                .with_transform(Matrix::translation(0.0, -1.0, 0.0))
+                .with_material(Material {
+                    reflective: 0.5,
+                    transparency: 0.5,
+                    refractive_index: 1.5,
+                    ..Default::default()
+                }),
+        );
+        w.add_object(
+            Sphere::new()
+                .with_transform(Matrix::translation(0.0, -3.5, -0.5))
+                .with_material(Material {
+                    color: Tuple::color(1.0, 0.0, 0.0),
+                    ambient: 0.5,
+                    ..Default::default()
+                }),
+        );
+        let v = 2f64.sqrt() / 2.0;
+        let r = Ray::new(Tuple::point(0.0, 0.0, -3.0), Tuple::vector(0.0, -v, v));
+        let xs = Intersections::new(vec![Intersection::new(2f64.sqrt(), &*w.objects[2])]);
+        let comps = xs[0].prepare_computations(&r, &xs);
+        assert_eq!(
+            w.shade_hit(&comps, 5).round(4),
+            Tuple::color(0.9339, 0.6964, 0.6924)
+        );
+    }
+}
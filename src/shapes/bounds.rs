@@ -0,0 +1,346 @@
+use crate::math::{Matrix, Ray, Tuple, EPSILON};
+
+/*
+ * Axis aligned bounding box. Boxes may be empty (min greater than max) or
+ * extend infinitely along any axis.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Tuple,
+    pub max: Tuple,
+}
+
+impl Default for Aabb {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+/*
+ * Where a ray enters and leaves the slab between min and max along one axis.
+ */
+fn check_axis(origin: f64, direction: f64, min: f64, max: f64) -> (f64, f64) {
+    let tmin_numerator = min - origin;
+    let tmax_numerator = max - origin;
+
+    let (tmin, tmax) = if direction.abs() >= EPSILON {
+        (tmin_numerator / direction, tmax_numerator / direction)
+    } else {
+        (
+            tmin_numerator * f64::INFINITY,
+            tmax_numerator * f64::INFINITY,
+        )
+    };
+
+    if tmin > tmax {
+        (tmax, tmin)
+    } else {
+        (tmin, tmax)
+    }
+}
+
+#[allow(dead_code)]
+impl Aabb {
+    pub fn new(min: Tuple, max: Tuple) -> Self {
+        Self { min, max }
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            min: Tuple::point(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+            max: Tuple::point(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+        }
+    }
+
+    pub fn infinite() -> Self {
+        Self {
+            min: Tuple::point(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+            max: Tuple::point(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..3).any(|i| self.min[i] > self.max[i])
+    }
+
+    pub fn is_finite(&self) -> bool {
+        (0..3).all(|i| self.min[i].is_finite() && self.max[i].is_finite())
+    }
+
+    pub fn center(&self) -> Tuple {
+        Tuple::point(
+            (self.min.x() + self.max.x()) / 2.0,
+            (self.min.y() + self.max.y()) / 2.0,
+            (self.min.z() + self.max.z()) / 2.0,
+        )
+    }
+
+    pub fn add_point(&mut self, point: &Tuple) {
+        for i in 0..3 {
+            self.min[i] = self.min[i].min(point[i]);
+            self.max[i] = self.max[i].max(point[i]);
+        }
+    }
+
+    pub fn merge(&mut self, other: &Aabb) {
+        if other.is_empty() {
+            return;
+        }
+        self.add_point(&other.min);
+        self.add_point(&other.max);
+    }
+
+    pub fn contains_point(&self, point: &Tuple) -> bool {
+        (0..3).all(|i| self.min[i] <= point[i] && point[i] <= self.max[i])
+    }
+
+    pub fn contains_box(&self, other: &Aabb) -> bool {
+        self.contains_point(&other.min) && self.contains_point(&other.max)
+    }
+
+    /*
+     * Bounds of this box after transformation, found by transforming all
+     * eight corners. Zero coefficients are skipped so infinite extents on
+     * untouched axes survive, axes that end up undefined become infinite.
+     */
+    pub fn transform(&self, m: &Matrix) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+
+        let mut result = Aabb::empty();
+        let mut undefined = [false; 3];
+        for corner in self.corners() {
+            let mut p = Tuple::point(0.0, 0.0, 0.0);
+            for (i, undefined) in undefined.iter_mut().enumerate() {
+                p[i] = (0..4)
+                    .filter(|j| m[i][*j] != 0.0)
+                    .map(|j| m[i][j] * corner[j])
+                    .sum();
+                if p[i].is_nan() {
+                    *undefined = true;
+                    p[i] = 0.0;
+                }
+            }
+            result.add_point(&p);
+        }
+
+        for (i, undefined) in undefined.iter().enumerate() {
+            if *undefined {
+                result.min[i] = f64::NEG_INFINITY;
+                result.max[i] = f64::INFINITY;
+            }
+        }
+        result
+    }
+
+    fn corners(&self) -> [Tuple; 8] {
+        let (min, max) = (self.min, self.max);
+        [
+            Tuple::point(min.x(), min.y(), min.z()),
+            Tuple::point(min.x(), min.y(), max.z()),
+            Tuple::point(min.x(), max.y(), min.z()),
+            Tuple::point(min.x(), max.y(), max.z()),
+            Tuple::point(max.x(), min.y(), min.z()),
+            Tuple::point(max.x(), min.y(), max.z()),
+            Tuple::point(max.x(), max.y(), min.z()),
+            Tuple::point(max.x(), max.y(), max.z()),
+        ]
+    }
+
+    pub fn intersects(&self, ray: &Ray) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+
+        let (xtmin, xtmax) = check_axis(
+            ray.origin.x(),
+            ray.direction.x(),
+            self.min.x(),
+            self.max.x(),
+        );
+        let (ytmin, ytmax) = check_axis(
+            ray.origin.y(),
+            ray.direction.y(),
+            self.min.y(),
+            self.max.y(),
+        );
+        let (ztmin, ztmax) = check_axis(
+            ray.origin.z(),
+            ray.direction.z(),
+            self.min.z(),
+            self.max.z(),
+        );
+
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+        tmin <= tmax
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::{FRAC_1_SQRT_2, PI, SQRT_2};
+
+    use crate::math::{Matrix, Ray, Tuple};
+    use crate::shapes::Aabb;
+
+    #[test]
+    fn creating_empty_box() {
+        let b = Aabb::empty();
+        assert!(b.is_empty());
+        assert_eq!(b.min.x(), f64::INFINITY);
+        assert_eq!(b.max.x(), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn creating_box_with_volume() {
+        let b = Aabb::new(Tuple::point(-1.0, -2.0, -3.0), Tuple::point(3.0, 2.0, 1.0));
+        assert_eq!(b.min, Tuple::point(-1.0, -2.0, -3.0));
+        assert_eq!(b.max, Tuple::point(3.0, 2.0, 1.0));
+        assert!(!b.is_empty());
+    }
+
+    #[test]
+    fn adding_points() {
+        let mut b = Aabb::empty();
+        b.add_point(&Tuple::point(-5.0, 2.0, 0.0));
+        b.add_point(&Tuple::point(7.0, 0.0, -3.0));
+        assert_eq!(b.min, Tuple::point(-5.0, 0.0, -3.0));
+        assert_eq!(b.max, Tuple::point(7.0, 2.0, 0.0));
+    }
+
+    #[test]
+    fn merging_boxes() {
+        let mut a = Aabb::new(Tuple::point(-5.0, -2.0, 0.0), Tuple::point(7.0, 4.0, 4.0));
+        let b = Aabb::new(Tuple::point(8.0, -7.0, -2.0), Tuple::point(14.0, 2.0, 8.0));
+        a.merge(&b);
+        assert_eq!(a.min, Tuple::point(-5.0, -7.0, -2.0));
+        assert_eq!(a.max, Tuple::point(14.0, 4.0, 8.0));
+    }
+
+    #[test]
+    fn merging_empty_box() {
+        let mut a = Aabb::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0));
+        a.merge(&Aabb::empty());
+        assert_eq!(a.min, Tuple::point(-1.0, -1.0, -1.0));
+        assert_eq!(a.max, Tuple::point(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn box_contains_point() {
+        let b = Aabb::new(Tuple::point(5.0, -2.0, 0.0), Tuple::point(11.0, 4.0, 7.0));
+        let cases = [
+            ((5.0, -2.0, 0.0), true),
+            ((11.0, 4.0, 7.0), true),
+            ((8.0, 1.0, 3.0), true),
+            ((3.0, 0.0, 3.0), false),
+            ((8.0, -4.0, 3.0), false),
+            ((8.0, 1.0, -1.0), false),
+            ((13.0, 1.0, 3.0), false),
+            ((8.0, 5.0, 3.0), false),
+            ((8.0, 1.0, 8.0), false),
+        ];
+        for ((x, y, z), expected) in cases {
+            assert_eq!(b.contains_point(&Tuple::point(x, y, z)), expected);
+        }
+    }
+
+    #[test]
+    fn box_contains_box() {
+        let b = Aabb::new(Tuple::point(5.0, -2.0, 0.0), Tuple::point(11.0, 4.0, 7.0));
+        let cases = [
+            ((5.0, -2.0, 0.0), (11.0, 4.0, 7.0), true),
+            ((6.0, -1.0, 1.0), (10.0, 3.0, 6.0), true),
+            ((4.0, -3.0, -1.0), (10.0, 3.0, 6.0), false),
+            ((6.0, -1.0, 1.0), (12.0, 5.0, 8.0), false),
+        ];
+        for ((x1, y1, z1), (x2, y2, z2), expected) in cases {
+            let other = Aabb::new(Tuple::point(x1, y1, z1), Tuple::point(x2, y2, z2));
+            assert_eq!(b.contains_box(&other), expected);
+        }
+    }
+
+    #[test]
+    fn transforming_box() {
+        let b = Aabb::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0));
+        let m = Matrix::rotation_y(PI / 4.0).rotate_x(PI / 4.0);
+        let b2 = b.transform(&m);
+        let (x, yz) = (SQRT_2, 1.0 + FRAC_1_SQRT_2);
+        assert_eq!(b2.min.round(4), Tuple::point(-x, -yz, -yz).round(4));
+        assert_eq!(b2.max.round(4), Tuple::point(x, yz, yz).round(4));
+    }
+
+    #[test]
+    fn transforming_infinite_box() {
+        let plane = Aabb::new(
+            Tuple::point(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+            Tuple::point(f64::INFINITY, 0.0, f64::INFINITY),
+        );
+        let moved = plane.transform(&Matrix::translation(0.0, -1.0, 0.0));
+        assert_eq!(moved.min.y(), -1.0);
+        assert_eq!(moved.max.y(), -1.0);
+        assert_eq!(moved.min.x(), f64::NEG_INFINITY);
+        assert_eq!(moved.max.z(), f64::INFINITY);
+
+        let tilted = plane.transform(&Matrix::rotation_x(PI / 4.0));
+        assert_eq!(tilted, Aabb::infinite());
+    }
+
+    #[test]
+    fn ray_intersects_cubic_box() {
+        let b = Aabb::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0));
+        let cases = [
+            ((5.0, 0.5, 0.0), (-1.0, 0.0, 0.0), true),
+            ((-5.0, 0.5, 0.0), (1.0, 0.0, 0.0), true),
+            ((0.5, 5.0, 0.0), (0.0, -1.0, 0.0), true),
+            ((0.5, -5.0, 0.0), (0.0, 1.0, 0.0), true),
+            ((0.5, 0.0, 5.0), (0.0, 0.0, -1.0), true),
+            ((0.5, 0.0, -5.0), (0.0, 0.0, 1.0), true),
+            ((0.0, 0.5, 0.0), (0.0, 0.0, 1.0), true),
+            ((-2.0, 0.0, 0.0), (2.0, 4.0, 6.0), false),
+            ((0.0, -2.0, 0.0), (6.0, 2.0, 4.0), false),
+            ((0.0, 0.0, -2.0), (4.0, 6.0, 2.0), false),
+            ((2.0, 0.0, 2.0), (0.0, 0.0, -1.0), false),
+            ((0.0, 2.0, 2.0), (0.0, -1.0, 0.0), false),
+            ((2.0, 2.0, 0.0), (-1.0, 0.0, 0.0), false),
+        ];
+        for ((ox, oy, oz), (dx, dy, dz), expected) in cases {
+            let r = Ray::new(Tuple::point(ox, oy, oz), Tuple::vector(dx, dy, dz).normal());
+            assert_eq!(b.intersects(&r), expected);
+        }
+    }
+
+    #[test]
+    fn ray_intersects_noncubic_box() {
+        let b = Aabb::new(Tuple::point(5.0, -2.0, 0.0), Tuple::point(11.0, 4.0, 7.0));
+        let cases = [
+            ((15.0, 1.0, 2.0), (-1.0, 0.0, 0.0), true),
+            ((-5.0, -1.0, 4.0), (1.0, 0.0, 0.0), true),
+            ((7.0, 6.0, 5.0), (0.0, -1.0, 0.0), true),
+            ((9.0, -5.0, 6.0), (0.0, 1.0, 0.0), true),
+            ((8.0, 2.0, 12.0), (0.0, 0.0, -1.0), true),
+            ((6.0, 0.0, -5.0), (0.0, 0.0, 1.0), true),
+            ((8.0, 1.0, 3.5), (0.0, 0.0, 1.0), true),
+            ((9.0, -1.0, -8.0), (2.0, 4.0, 6.0), false),
+            ((8.0, 3.0, -4.0), (6.0, 2.0, 4.0), false),
+            ((9.0, -1.0, -2.0), (4.0, 6.0, 2.0), false),
+            ((4.0, 0.0, 9.0), (0.0, 0.0, -1.0), false),
+            ((8.0, 6.0, -1.0), (0.0, -1.0, 0.0), false),
+            ((12.0, 5.0, 4.0), (-1.0, 0.0, 0.0), false),
+        ];
+        for ((ox, oy, oz), (dx, dy, dz), expected) in cases {
+            let r = Ray::new(Tuple::point(ox, oy, oz), Tuple::vector(dx, dy, dz).normal());
+            assert_eq!(b.intersects(&r), expected);
+        }
+    }
+
+    #[test]
+    fn finite_boxes() {
+        let b = Aabb::new(Tuple::point(-1.0, 0.0, 2.0), Tuple::point(3.0, 4.0, 6.0));
+        assert!(b.is_finite());
+        assert_eq!(b.center(), Tuple::point(1.0, 2.0, 4.0));
+        assert!(!Aabb::infinite().is_finite());
+        assert!(!Aabb::empty().is_finite());
+    }
+}
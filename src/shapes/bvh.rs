@@ -0,0 +1,189 @@
+use crate::math::Ray;
+
+use super::Aabb;
+
+/* How many children a node may hold before it is split */
+pub const BVH_THRESHOLD: usize = 4;
+
+/*
+ * Bounding volume hierarchy over the children of a group. Nodes refer to
+ * children by their index so the group keeps owning its shapes.
+ */
+#[derive(Debug)]
+pub struct Bvh {
+    root: Node,
+}
+
+#[derive(Debug)]
+struct Node {
+    bounds: Aabb,
+    /* Children tested whenever the ray hits this node */
+    shapes: Vec<usize>,
+    split: Option<Box<(Node, Node)>>,
+}
+
+impl Bvh {
+    /*
+     * Builds the hierarchy from the bounds of each child, nodes holding more
+     * than `threshold` children are split in two along their longest axis.
+     */
+    pub fn build(boxes: &[Aabb], threshold: usize) -> Self {
+        let indices = (0..boxes.len()).collect();
+        Self {
+            root: Node::build(boxes, indices, threshold.max(1)),
+        }
+    }
+
+    /* Indices of the children whose bounds the ray might hit */
+    pub fn candidates(&self, ray: &Ray) -> Vec<usize> {
+        let mut result = vec![];
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            if !node.bounds.intersects(ray) {
+                continue;
+            }
+            result.extend(&node.shapes);
+            if let Some(split) = &node.split {
+                stack.push(&split.0);
+                stack.push(&split.1);
+            }
+        }
+        result
+    }
+}
+
+impl Node {
+    fn build(boxes: &[Aabb], indices: Vec<usize>, threshold: usize) -> Self {
+        let mut bounds = Aabb::empty();
+        for i in &indices {
+            bounds.merge(&boxes[*i]);
+        }
+
+        /* Unbounded children can't be partitioned so they stay at this level */
+        let (finite, mut shapes): (Vec<usize>, Vec<usize>) =
+            indices.into_iter().partition(|i| boxes[*i].is_finite());
+
+        if finite.len() <= threshold {
+            shapes.extend(finite);
+            return Self {
+                bounds,
+                shapes,
+                split: None,
+            };
+        }
+
+        /* Split at the middle of the longest axis of the children's centers */
+        let mut centers = Aabb::empty();
+        for i in &finite {
+            centers.add_point(&boxes[*i].center());
+        }
+        let extent = centers.max - centers.min;
+        let axis = (0..3)
+            .max_by(|a, b| extent[*a].total_cmp(&extent[*b]))
+            .unwrap();
+        if extent[axis] == 0.0 {
+            /* Every child shares the same center, nothing left to split */
+            shapes.extend(finite);
+            return Self {
+                bounds,
+                shapes,
+                split: None,
+            };
+        }
+
+        let middle = centers.min[axis] + extent[axis] / 2.0;
+        let (left, right) = finite
+            .into_iter()
+            .partition(|i| boxes[*i].center()[axis] < middle);
+
+        Self {
+            bounds,
+            shapes,
+            split: Some(Box::new((
+                Node::build(boxes, left, threshold),
+                Node::build(boxes, right, threshold),
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::{Ray, Tuple};
+    use crate::shapes::Aabb;
+
+    use super::Bvh;
+
+    fn unit_box_at(x: f64, y: f64, z: f64) -> Aabb {
+        Aabb::new(
+            Tuple::point(x - 1.0, y - 1.0, z - 1.0),
+            Tuple::point(x + 1.0, y + 1.0, z + 1.0),
+        )
+    }
+
+    #[test]
+    fn below_threshold_is_single_leaf() {
+        let boxes = [unit_box_at(-5.0, 0.0, 0.0), unit_box_at(5.0, 0.0, 0.0)];
+        let bvh = Bvh::build(&boxes, 2);
+        assert!(bvh.root.split.is_none());
+        assert_eq!(bvh.root.shapes, vec![0, 1]);
+    }
+
+    #[test]
+    fn splits_along_longest_axis() {
+        let boxes = [
+            unit_box_at(-5.0, 0.0, 0.0),
+            unit_box_at(5.0, 1.0, 0.0),
+            unit_box_at(-4.0, 0.0, 1.0),
+        ];
+        let bvh = Bvh::build(&boxes, 1);
+        let split = bvh.root.split.as_ref().unwrap();
+        assert!(bvh.root.shapes.is_empty());
+        assert_eq!(split.1.shapes, vec![1]);
+        assert_eq!(split.1.bounds, boxes[1]);
+        assert!(split.0.split.is_some());
+    }
+
+    #[test]
+    fn unbounded_children_stay_at_root() {
+        let boxes = [
+            unit_box_at(-5.0, 0.0, 0.0),
+            Aabb::infinite(),
+            unit_box_at(5.0, 0.0, 0.0),
+        ];
+        let bvh = Bvh::build(&boxes, 1);
+        assert_eq!(bvh.root.shapes, vec![1]);
+        assert!(bvh.root.split.is_some());
+    }
+
+    #[test]
+    fn identical_children_are_not_split() {
+        let boxes = [unit_box_at(1.0, 1.0, 1.0); 5];
+        let bvh = Bvh::build(&boxes, 2);
+        assert!(bvh.root.split.is_none());
+        assert_eq!(bvh.root.shapes.len(), 5);
+    }
+
+    #[test]
+    fn candidates_skip_missed_nodes() {
+        let boxes = [
+            unit_box_at(-5.0, 0.0, 0.0),
+            unit_box_at(5.0, 0.0, 0.0),
+            unit_box_at(-5.0, 0.0, 5.0),
+            unit_box_at(5.0, 0.0, 5.0),
+        ];
+        let bvh = Bvh::build(&boxes, 1);
+        let r = Ray::new(Tuple::point(5.0, 0.0, -10.0), Tuple::vector(0.0, 0.0, 1.0));
+        let mut candidates = bvh.candidates(&r);
+        candidates.sort();
+        assert_eq!(candidates, vec![1, 3]);
+    }
+
+    #[test]
+    fn candidates_when_missing_everything() {
+        let boxes = [unit_box_at(-5.0, 0.0, 0.0), unit_box_at(5.0, 0.0, 0.0)];
+        let bvh = Bvh::build(&boxes, 1);
+        let r = Ray::new(Tuple::point(0.0, 10.0, -10.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert!(bvh.candidates(&r).is_empty());
+    }
+}
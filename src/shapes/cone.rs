@@ -0,0 +1,216 @@
+use crate::math::{Ray, Tuple, EPSILON};
+use crate::scene::{Intersection, Intersections};
+
+use super::cylinder::check_cap;
+use super::{Aabb, Shape, ShapeData};
+
+/*
+ * A double-napped cone around the y axis with its tip at the origin and a
+ * radius equal to |y|. Truncation and caps work as for `Cylinder`.
+ */
+#[derive(Debug, Clone)]
+pub struct Cone {
+    data: ShapeData,
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+}
+
+impl Default for Cone {
+    fn default() -> Self {
+        Self {
+            data: ShapeData::default(),
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl Cone {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn truncated(minimum: f64, maximum: f64, closed: bool) -> Self {
+        Self {
+            minimum,
+            maximum,
+            closed,
+            ..Default::default()
+        }
+    }
+}
+
+impl Cone {
+    fn intersect_caps<'a>(&'a self, ray: &Ray, xs: &mut Vec<Intersection<'a>>) {
+        if !self.closed || ray.direction.y().abs() < EPSILON {
+            return;
+        }
+
+        let t = (self.minimum - ray.origin.y()) / ray.direction.y();
+        if check_cap(ray, t, self.minimum.abs()) {
+            xs.push(Intersection::new(t, self));
+        }
+
+        let t = (self.maximum - ray.origin.y()) / ray.direction.y();
+        if check_cap(ray, t, self.maximum.abs()) {
+            xs.push(Intersection::new(t, self));
+        }
+    }
+}
+
+impl Shape for Cone {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let mut xs = vec![];
+        let (o, d) = (ray.origin, ray.direction);
+
+        let a = d.x().powi(2) - d.y().powi(2) + d.z().powi(2);
+        let b = 2.0 * o.x() * d.x() - 2.0 * o.y() * d.y() + 2.0 * o.z() * d.z();
+        let c = o.x().powi(2) - o.y().powi(2) + o.z().powi(2);
+
+        if a.abs() < EPSILON {
+            /* The ray is parallel to one of the halves, a single hit at most */
+            if b.abs() >= EPSILON {
+                let t = -c / (2.0 * b);
+                let y = o.y() + t * d.y();
+                if self.minimum < y && y < self.maximum {
+                    xs.push(Intersection::new(t, self));
+                }
+            }
+        } else {
+            let discriminant = b.powi(2) - 4.0 * a * c;
+            if discriminant < 0.0 {
+                return Intersections::empty();
+            }
+
+            let t0 = (-b - discriminant.sqrt()) / (2.0 * a);
+            let t1 = (-b + discriminant.sqrt()) / (2.0 * a);
+            for t in [t0, t1] {
+                let y = o.y() + t * d.y();
+                if self.minimum < y && y < self.maximum {
+                    xs.push(Intersection::new(t, self));
+                }
+            }
+        }
+
+        self.intersect_caps(ray, &mut xs);
+        Intersections::new(xs)
+    }
+
+    fn local_normal_at(&self, point: &Tuple) -> Tuple {
+        let dist = point.x().powi(2) + point.z().powi(2);
+        if dist < self.maximum.powi(2) && point.y() >= self.maximum - EPSILON {
+            Tuple::vector(0.0, 1.0, 0.0)
+        } else if dist < self.minimum.powi(2) && point.y() <= self.minimum + EPSILON {
+            Tuple::vector(0.0, -1.0, 0.0)
+        } else {
+            let y = dist.sqrt();
+            let y = if point.y() > 0.0 { -y } else { y };
+            Tuple::vector(point.x(), y, point.z())
+        }
+    }
+
+    fn bounds(&self) -> Aabb {
+        let limit = self.minimum.abs().max(self.maximum.abs());
+        Aabb::new(
+            Tuple::point(-limit, self.minimum, -limit),
+            Tuple::point(limit, self.maximum, limit),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::{Ray, Tuple};
+    use crate::shapes::{Cone, Shape};
+
+    #[test]
+    fn intersecting_cone() {
+        let shape = Cone::new();
+        let cases = [
+            ((0.0, 0.0, -5.0), (0.0, 0.0, 1.0), 5.0, 5.0),
+            ((0.0, 0.0, -5.0), (1.0, 1.0, 1.0), 8.66025, 8.66025),
+            ((1.0, 1.0, -5.0), (-0.5, -1.0, 1.0), 4.55006, 49.44994),
+        ];
+        for ((ox, oy, oz), (dx, dy, dz), t0, t1) in cases {
+            let direction = Tuple::vector(dx, dy, dz).normal();
+            let r = Ray::new(Tuple::point(ox, oy, oz), direction);
+            let xs = shape.local_intersect(&r);
+            assert_eq!(xs.len(), 2);
+            assert!((xs[0].t - t0).abs() < 0.0001);
+            assert!((xs[1].t - t1).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn ray_parallel_to_one_half() {
+        let shape = Cone::new();
+        let direction = Tuple::vector(0.0, 1.0, 1.0).normal();
+        let r = Ray::new(Tuple::point(0.0, 0.0, -1.0), direction);
+        let xs = shape.local_intersect(&r);
+        assert_eq!(xs.len(), 1);
+        assert!((xs[0].t - 0.35355).abs() < 0.0001);
+    }
+
+    #[test]
+    fn intersecting_caps() {
+        let shape = Cone::truncated(-0.5, 0.5, true);
+        let cases = [
+            ((0.0, 0.0, -5.0), (0.0, 1.0, 0.0), 0),
+            ((0.0, 0.0, -0.25), (0.0, 1.0, 1.0), 2),
+            ((0.0, 0.0, -0.25), (0.0, 1.0, 0.0), 4),
+        ];
+        for ((ox, oy, oz), (dx, dy, dz), count) in cases {
+            let direction = Tuple::vector(dx, dy, dz).normal();
+            let r = Ray::new(Tuple::point(ox, oy, oz), direction);
+            assert_eq!(shape.local_intersect(&r).len(), count);
+        }
+    }
+
+    #[test]
+    fn normal_on_cone() {
+        let shape = Cone::new();
+        let cases = [
+            ((0.0, 0.0, 0.0), (0.0, 0.0, 0.0)),
+            ((1.0, 1.0, 1.0), (1.0, -(2f64.sqrt()), 1.0)),
+            ((-1.0, -1.0, 0.0), (-1.0, 1.0, 0.0)),
+        ];
+        for ((px, py, pz), (nx, ny, nz)) in cases {
+            let n = shape.local_normal_at(&Tuple::point(px, py, pz));
+            assert_eq!(n, Tuple::vector(nx, ny, nz));
+        }
+    }
+
+    #[test]
+    fn normal_on_caps() {
+        let shape = Cone::truncated(-1.0, 1.0, true);
+        assert_eq!(
+            shape.local_normal_at(&Tuple::point(0.5, 1.0, 0.0)),
+            Tuple::vector(0.0, 1.0, 0.0)
+        );
+        assert_eq!(
+            shape.local_normal_at(&Tuple::point(0.0, -1.0, 0.5)),
+            Tuple::vector(0.0, -1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn bounds() {
+        let b = Cone::new().bounds();
+        assert_eq!(b.min.x(), f64::NEG_INFINITY);
+        assert_eq!(b.max.y(), f64::INFINITY);
+        let b = Cone::truncated(-5.0, 3.0, false).bounds();
+        assert_eq!(b.min, Tuple::point(-5.0, -5.0, -5.0));
+        assert_eq!(b.max, Tuple::point(5.0, 3.0, 5.0));
+    }
+}
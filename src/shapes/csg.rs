@@ -0,0 +1,351 @@
+use crate::graphics::Material;
+use crate::math::{Matrix, Ray, Tuple};
+use crate::scene::{Intersection, Intersections};
+
+use super::{Aabb, Shape, ShapeData};
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl CsgOperation {
+    /*
+     * Whether a hit on the left or right child is kept, given whether the ray
+     * is currently inside of the left and right child.
+     */
+    pub fn allows(&self, left_hit: bool, in_left: bool, in_right: bool) -> bool {
+        match self {
+            CsgOperation::Union => (left_hit && !in_right) || (!left_hit && !in_left),
+            CsgOperation::Intersection => (left_hit && in_right) || (!left_hit && in_left),
+            CsgOperation::Difference => (left_hit && !in_right) || (!left_hit && in_left),
+        }
+    }
+}
+
+/*
+ * Constructive solid geometry, two shapes combined into one by an operation.
+ * Like a group the transforms of the children are relative to the csg.
+ */
+#[derive(Debug)]
+pub struct Csg {
+    data: ShapeData,
+    operation: CsgOperation,
+    left: Box<dyn Shape>,
+    right: Box<dyn Shape>,
+    /* The children are fixed once created so their bounds are kept */
+    bounds: Aabb,
+}
+
+#[allow(dead_code)]
+impl Csg {
+    pub fn new<L: Shape + 'static, R: Shape + 'static>(
+        operation: CsgOperation,
+        left: L,
+        right: R,
+    ) -> Self {
+        Self::from_boxed(operation, Box::new(left), Box::new(right))
+    }
+
+    pub fn from_boxed(
+        operation: CsgOperation,
+        mut left: Box<dyn Shape>,
+        mut right: Box<dyn Shape>,
+    ) -> Self {
+        let data = ShapeData::default();
+        left.insert_parents(0, &data.child_parents());
+        right.insert_parents(0, &data.child_parents());
+        let mut bounds = left.parent_space_bounds();
+        /* Nothing outside the left child survives a difference */
+        if operation != CsgOperation::Difference {
+            bounds.merge(&right.parent_space_bounds());
+        }
+        Self {
+            data,
+            operation,
+            left,
+            right,
+            bounds,
+        }
+    }
+
+    pub fn operation(&self) -> CsgOperation {
+        self.operation
+    }
+
+    pub fn left(&self) -> &dyn Shape {
+        &*self.left
+    }
+
+    pub fn right(&self) -> &dyn Shape {
+        &*self.right
+    }
+
+    /*
+     * Keeps the intersections that lie on the surface of the combined shape,
+     * the list must be sorted.
+     */
+    pub fn filter_intersections<'a>(&self, xs: &[Intersection<'a>]) -> Intersections<'a> {
+        let mut in_left = false;
+        let mut in_right = false;
+        let mut result = vec![];
+
+        for i in xs {
+            let left_hit = self.left.includes(i.object);
+            if self.operation.allows(left_hit, in_left, in_right) {
+                result.push(*i);
+            }
+            if left_hit {
+                in_left = !in_left;
+            } else {
+                in_right = !in_right;
+            }
+        }
+
+        Intersections::new(result)
+    }
+}
+
+impl Shape for Csg {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        if !self.bounds.intersects(ray) {
+            return Intersections::empty();
+        }
+
+        let mut xs = self.left.intersect(ray);
+        xs.merge(self.right.intersect(ray));
+        self.filter_intersections(&xs)
+    }
+
+    fn local_normal_at(&self, _point: &Tuple) -> Tuple {
+        panic!("Csg shapes have no surface, normals come from the child that was hit");
+    }
+
+    fn bounds(&self) -> Aabb {
+        self.bounds
+    }
+
+    /* Like groups, csg shapes hand their material to the children */
+    fn set_material(&mut self, material: Material) {
+        self.left.set_material(material.clone());
+        self.right.set_material(material.clone());
+        self.data.set_material(material);
+    }
+
+    fn includes(&self, object: &dyn Shape) -> bool {
+        self.left.includes(object) || self.right.includes(object)
+    }
+
+    fn divide(&mut self, threshold: usize) {
+        self.left.divide(threshold);
+        self.right.divide(threshold);
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.data.set_transform(transform);
+        let depth = self.data.depth();
+        let inverse = self.data.inverse().clone();
+        self.left.replace_parent(depth, &inverse);
+        self.right.replace_parent(depth, &inverse);
+    }
+
+    fn insert_parents(&mut self, depth: usize, parents: &[Matrix]) {
+        self.data.insert_parents(depth, parents);
+        self.left.insert_parents(depth, parents);
+        self.right.insert_parents(depth, parents);
+    }
+
+    fn replace_parent(&mut self, depth: usize, inverse: &Matrix) {
+        self.data.replace_parent(depth, inverse);
+        self.left.replace_parent(depth, inverse);
+        self.right.replace_parent(depth, inverse);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    use crate::graphics::Material;
+    use crate::math::{Matrix, Ray, Tuple};
+    use crate::scene::Intersection;
+    use crate::shapes::{Csg, CsgOperation, Cube, Group, Shape, Sphere, TestShape};
+
+    #[test]
+    fn creating_csg() {
+        let c = Csg::new(CsgOperation::Union, Sphere::new(), Cube::new());
+        assert_eq!(c.operation(), CsgOperation::Union);
+        assert_eq!(c.left().data().depth(), 1);
+        assert_eq!(c.right().data().depth(), 1);
+    }
+
+    #[test]
+    fn evaluating_rules() {
+        use CsgOperation::*;
+        let cases = [
+            (Union, true, true, true, false),
+            (Union, true, true, false, true),
+            (Union, true, false, true, false),
+            (Union, true, false, false, true),
+            (Union, false, true, true, false),
+            (Union, false, true, false, false),
+            (Union, false, false, true, true),
+            (Union, false, false, false, true),
+            (Intersection, true, true, true, true),
+            (Intersection, true, true, false, false),
+            (Intersection, true, false, true, true),
+            (Intersection, true, false, false, false),
+            (Intersection, false, true, true, true),
+            (Intersection, false, true, false, true),
+            (Intersection, false, false, true, false),
+            (Intersection, false, false, false, false),
+            (Difference, true, true, true, false),
+            (Difference, true, true, false, true),
+            (Difference, true, false, true, false),
+            (Difference, true, false, false, true),
+            (Difference, false, true, true, true),
+            (Difference, false, true, false, true),
+            (Difference, false, false, true, false),
+            (Difference, false, false, false, false),
+        ];
+        for (op, lhit, inl, inr, result) in cases {
+            assert_eq!(
+                op.allows(lhit, inl, inr),
+                result,
+                "{:?} {} {} {}",
+                op,
+                lhit,
+                inl,
+                inr
+            );
+        }
+    }
+
+    #[test]
+    fn filtering_intersections() {
+        for (op, x0, x1) in [
+            (CsgOperation::Union, 0, 3),
+            (CsgOperation::Intersection, 1, 2),
+            (CsgOperation::Difference, 0, 1),
+        ] {
+            let c = Csg::new(op, Sphere::new(), Cube::new());
+            let (s1, s2) = (c.left(), c.right());
+            let xs = [
+                Intersection::new(1.0, s1),
+                Intersection::new(2.0, s2),
+                Intersection::new(3.0, s1),
+                Intersection::new(4.0, s2),
+            ];
+            let result = c.filter_intersections(&xs);
+            assert_eq!(result.len(), 2);
+            assert_eq!(result[0], xs[x0]);
+            assert_eq!(result[1], xs[x1]);
+        }
+    }
+
+    #[test]
+    fn ray_misses_csg() {
+        let c = Csg::new(CsgOperation::Union, Sphere::new(), Cube::new());
+        let r = Ray::new(Tuple::point(0.0, 2.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert!(c.local_intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn ray_hits_csg() {
+        let s2 = Sphere::new().with_transform(Matrix::translation(0.0, 0.0, 0.5));
+        let c = Csg::new(CsgOperation::Union, Sphere::new(), s2);
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = c.local_intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 4.0);
+        assert!(std::ptr::addr_eq(xs[0].object, c.left()));
+        assert_eq!(xs[1].t, 6.5);
+        assert!(std::ptr::addr_eq(xs[1].object, c.right()));
+    }
+
+    #[test]
+    fn includes_recurses_into_groups() {
+        let g = Group::new().with_child(Sphere::new());
+        let c = Csg::new(CsgOperation::Difference, Cube::new(), g);
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = c.right().intersect(&r);
+        assert!(c.right().includes(xs[0].object));
+        assert!(!c.left().includes(xs[0].object));
+        assert!(c.includes(xs[0].object));
+    }
+
+    #[test]
+    fn difference_with_group_carves_hole() {
+        let g = Group::new()
+            .with_child(Sphere::new().with_transform(Matrix::translation(0.0, 0.0, -1.0)));
+        let c = Csg::new(CsgOperation::Difference, Cube::new(), g);
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = c.intersect(&r);
+        let ts: Vec<f64> = xs.iter().map(|i| i.t).collect();
+        assert_eq!(ts, vec![5.0, 6.0]);
+    }
+
+    #[test]
+    fn transforming_csg_moves_children() {
+        let child = TestShape::default();
+        let saved = child.saved_ray.clone();
+        let c = Csg::new(CsgOperation::Union, child, Sphere::new())
+            .with_transform(Matrix::scaling(2.0, 2.0, 2.0));
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = c.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(
+            saved.lock().unwrap().unwrap().origin,
+            Tuple::point(0.0, 0.0, -2.5)
+        );
+        let n = xs[0].object.normal_at(&Tuple::point(
+            0.0,
+            2.0 * FRAC_1_SQRT_2,
+            -2.0 * FRAC_1_SQRT_2,
+        ));
+        assert_eq!(
+            n.round(5),
+            Tuple::vector(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2).round(5)
+        );
+    }
+
+    #[test]
+    fn bounds() {
+        let right = Sphere::new().with_transform(Matrix::translation(2.0, 0.0, 0.0));
+        let c = Csg::new(CsgOperation::Union, Sphere::new(), right);
+        assert_eq!(c.bounds().min, Tuple::point(-1.0, -1.0, -1.0));
+        assert_eq!(c.bounds().max, Tuple::point(3.0, 1.0, 1.0));
+
+        let right = Sphere::new().with_transform(Matrix::translation(2.0, 0.0, 0.0));
+        let c = Csg::new(CsgOperation::Difference, Sphere::new(), right);
+        assert_eq!(c.bounds().max, Tuple::point(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn material_is_passed_to_children() {
+        let m = Material {
+            ambient: 1.0,
+            ..Default::default()
+        };
+        let mut c = Csg::new(
+            CsgOperation::Union,
+            Sphere::new(),
+            Group::new().with_child(Cube::new()),
+        );
+        c.set_material(m.clone());
+        assert_eq!(c.left().material(), &m);
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert!(c.intersect(&r).iter().all(|i| i.object.material() == &m));
+    }
+}
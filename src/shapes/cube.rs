@@ -0,0 +1,168 @@
+use crate::math::{Ray, Tuple, EPSILON};
+use crate::scene::{Intersection, Intersections};
+
+use super::{Aabb, Shape, ShapeData};
+
+/*
+ * An axis aligned cube spanning -1 to 1 on every axis.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct Cube {
+    data: ShapeData,
+}
+
+#[allow(dead_code)]
+impl Cube {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/*
+ * Where a ray enters and leaves the slab between -1 and 1 along one axis.
+ */
+fn check_axis(origin: f64, direction: f64) -> (f64, f64) {
+    let tmin_numerator = -1.0 - origin;
+    let tmax_numerator = 1.0 - origin;
+
+    let (tmin, tmax) = if direction.abs() >= EPSILON {
+        (tmin_numerator / direction, tmax_numerator / direction)
+    } else {
+        (
+            tmin_numerator * f64::INFINITY,
+            tmax_numerator * f64::INFINITY,
+        )
+    };
+
+    if tmin > tmax {
+        (tmax, tmin)
+    } else {
+        (tmin, tmax)
+    }
+}
+
+impl Shape for Cube {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let (xtmin, xtmax) = check_axis(ray.origin.x(), ray.direction.x());
+        let (ytmin, ytmax) = check_axis(ray.origin.y(), ray.direction.y());
+        let (ztmin, ztmax) = check_axis(ray.origin.z(), ray.direction.z());
+
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+        if tmin > tmax {
+            return Intersections::empty();
+        }
+
+        Intersections::new(vec![
+            Intersection::new(tmin, self),
+            Intersection::new(tmax, self),
+        ])
+    }
+
+    fn local_normal_at(&self, point: &Tuple) -> Tuple {
+        let (x, y, z) = (point.x().abs(), point.y().abs(), point.z().abs());
+        let maxc = x.max(y).max(z);
+
+        if maxc == x {
+            Tuple::vector(point.x(), 0.0, 0.0)
+        } else if maxc == y {
+            Tuple::vector(0.0, point.y(), 0.0)
+        } else {
+            Tuple::vector(0.0, 0.0, point.z())
+        }
+    }
+
+    fn bounds(&self) -> Aabb {
+        Aabb::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::{Matrix, Ray, Tuple};
+    use crate::shapes::{Cube, Shape};
+
+    #[test]
+    fn ray_intersects_cube() {
+        let c = Cube::new();
+        let cases = [
+            ((5.0, 0.5, 0.0), (-1.0, 0.0, 0.0), 4.0, 6.0),
+            ((-5.0, 0.5, 0.0), (1.0, 0.0, 0.0), 4.0, 6.0),
+            ((0.5, 5.0, 0.0), (0.0, -1.0, 0.0), 4.0, 6.0),
+            ((0.5, -5.0, 0.0), (0.0, 1.0, 0.0), 4.0, 6.0),
+            ((0.5, 0.0, 5.0), (0.0, 0.0, -1.0), 4.0, 6.0),
+            ((0.5, 0.0, -5.0), (0.0, 0.0, 1.0), 4.0, 6.0),
+            ((0.0, 0.5, 0.0), (0.0, 0.0, 1.0), -1.0, 1.0),
+        ];
+        for ((ox, oy, oz), (dx, dy, dz), t1, t2) in cases {
+            let r = Ray::new(Tuple::point(ox, oy, oz), Tuple::vector(dx, dy, dz));
+            let xs = c.local_intersect(&r);
+            assert_eq!(xs.len(), 2);
+            assert_eq!(xs[0].t, t1);
+            assert_eq!(xs[1].t, t2);
+        }
+    }
+
+    #[test]
+    fn ray_misses_cube() {
+        let c = Cube::new();
+        let cases = [
+            ((-2.0, 0.0, 0.0), (0.2673, 0.5345, 0.8018)),
+            ((0.0, -2.0, 0.0), (0.8018, 0.2673, 0.5345)),
+            ((0.0, 0.0, -2.0), (0.5345, 0.8018, 0.2673)),
+            ((2.0, 0.0, 2.0), (0.0, 0.0, -1.0)),
+            ((0.0, 2.0, 2.0), (0.0, -1.0, 0.0)),
+            ((2.0, 2.0, 0.0), (-1.0, 0.0, 0.0)),
+        ];
+        for ((ox, oy, oz), (dx, dy, dz)) in cases {
+            let r = Ray::new(Tuple::point(ox, oy, oz), Tuple::vector(dx, dy, dz));
+            assert!(c.local_intersect(&r).is_empty());
+        }
+    }
+
+    #[test]
+    fn normal_on_surface() {
+        let c = Cube::new();
+        let cases = [
+            ((1.0, 0.5, -0.8), (1.0, 0.0, 0.0)),
+            ((-1.0, -0.2, 0.9), (-1.0, 0.0, 0.0)),
+            ((-0.4, 1.0, -0.1), (0.0, 1.0, 0.0)),
+            ((0.3, -1.0, -0.7), (0.0, -1.0, 0.0)),
+            ((-0.6, 0.3, 1.0), (0.0, 0.0, 1.0)),
+            ((0.4, 0.4, -1.0), (0.0, 0.0, -1.0)),
+            ((1.0, 1.0, 1.0), (1.0, 0.0, 0.0)),
+            ((-1.0, -1.0, -1.0), (-1.0, 0.0, 0.0)),
+        ];
+        for ((px, py, pz), (nx, ny, nz)) in cases {
+            let n = c.local_normal_at(&Tuple::point(px, py, pz));
+            assert_eq!(n, Tuple::vector(nx, ny, nz));
+        }
+    }
+
+    #[test]
+    fn intersecting_transformed_cube() {
+        let c = Cube::new().with_transform(Matrix::scaling(2.0, 1.0, 1.0).translate(0.0, 1.0, 0.0));
+        let r = Ray::new(Tuple::point(-5.0, 1.5, 0.0), Tuple::vector(1.0, 0.0, 0.0));
+        let xs = c.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 3.0);
+        assert_eq!(xs[1].t, 7.0);
+        let n = c.normal_at(&Tuple::point(-2.0, 1.5, 0.0));
+        assert_eq!(n, Tuple::vector(-1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn bounds() {
+        let c = Cube::new();
+        assert_eq!(c.bounds().min, Tuple::point(-1.0, -1.0, -1.0));
+        assert_eq!(c.bounds().max, Tuple::point(1.0, 1.0, 1.0));
+    }
+}
@@ -0,0 +1,251 @@
+use crate::math::{Ray, Tuple, EPSILON};
+use crate::scene::{Intersection, Intersections};
+
+use super::{Aabb, Shape, ShapeData};
+
+/*
+ * A cylinder of radius 1 around the y axis, optionally truncated between
+ * `minimum` and `maximum` (exclusive) and capped when `closed`.
+ */
+#[derive(Debug, Clone)]
+pub struct Cylinder {
+    data: ShapeData,
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+}
+
+impl Default for Cylinder {
+    fn default() -> Self {
+        Self {
+            data: ShapeData::default(),
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl Cylinder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn truncated(minimum: f64, maximum: f64, closed: bool) -> Self {
+        Self {
+            minimum,
+            maximum,
+            closed,
+            ..Default::default()
+        }
+    }
+}
+
+/*
+ * Is the intersection at t within the given radius from the y axis.
+ */
+pub(super) fn check_cap(ray: &Ray, t: f64, radius: f64) -> bool {
+    let x = ray.origin.x() + t * ray.direction.x();
+    let z = ray.origin.z() + t * ray.direction.z();
+    x.powi(2) + z.powi(2) <= radius.powi(2) + EPSILON
+}
+
+impl Cylinder {
+    fn intersect_caps<'a>(&'a self, ray: &Ray, xs: &mut Vec<Intersection<'a>>) {
+        if !self.closed || ray.direction.y().abs() < EPSILON {
+            return;
+        }
+
+        let t = (self.minimum - ray.origin.y()) / ray.direction.y();
+        if check_cap(ray, t, 1.0) {
+            xs.push(Intersection::new(t, self));
+        }
+
+        let t = (self.maximum - ray.origin.y()) / ray.direction.y();
+        if check_cap(ray, t, 1.0) {
+            xs.push(Intersection::new(t, self));
+        }
+    }
+}
+
+impl Shape for Cylinder {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let mut xs = vec![];
+
+        let a = ray.direction.x().powi(2) + ray.direction.z().powi(2);
+        /* Rays parallel to the y axis can only hit the caps */
+        if a.abs() >= EPSILON {
+            let b =
+                2.0 * ray.origin.x() * ray.direction.x() + 2.0 * ray.origin.z() * ray.direction.z();
+            let c = ray.origin.x().powi(2) + ray.origin.z().powi(2) - 1.0;
+            let discriminant = b.powi(2) - 4.0 * a * c;
+            if discriminant < 0.0 {
+                return Intersections::empty();
+            }
+
+            let t0 = (-b - discriminant.sqrt()) / (2.0 * a);
+            let t1 = (-b + discriminant.sqrt()) / (2.0 * a);
+            for t in [t0, t1] {
+                let y = ray.origin.y() + t * ray.direction.y();
+                if self.minimum < y && y < self.maximum {
+                    xs.push(Intersection::new(t, self));
+                }
+            }
+        }
+
+        self.intersect_caps(ray, &mut xs);
+        Intersections::new(xs)
+    }
+
+    fn local_normal_at(&self, point: &Tuple) -> Tuple {
+        let dist = point.x().powi(2) + point.z().powi(2);
+        if dist < 1.0 && point.y() >= self.maximum - EPSILON {
+            Tuple::vector(0.0, 1.0, 0.0)
+        } else if dist < 1.0 && point.y() <= self.minimum + EPSILON {
+            Tuple::vector(0.0, -1.0, 0.0)
+        } else {
+            Tuple::vector(point.x(), 0.0, point.z())
+        }
+    }
+
+    fn bounds(&self) -> Aabb {
+        Aabb::new(
+            Tuple::point(-1.0, self.minimum, -1.0),
+            Tuple::point(1.0, self.maximum, 1.0),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::{Ray, Tuple};
+    use crate::shapes::{Cylinder, Shape};
+
+    #[test]
+    fn ray_misses_cylinder() {
+        let cyl = Cylinder::new();
+        let cases = [
+            ((1.0, 0.0, 0.0), (0.0, 1.0, 0.0)),
+            ((0.0, 0.0, 0.0), (0.0, 1.0, 0.0)),
+            ((0.0, 0.0, -5.0), (1.0, 1.0, 1.0)),
+        ];
+        for ((ox, oy, oz), (dx, dy, dz)) in cases {
+            let direction = Tuple::vector(dx, dy, dz).normal();
+            let r = Ray::new(Tuple::point(ox, oy, oz), direction);
+            assert!(cyl.local_intersect(&r).is_empty());
+        }
+    }
+
+    #[test]
+    fn ray_strikes_cylinder() {
+        let cyl = Cylinder::new();
+        let cases = [
+            ((1.0, 0.0, -5.0), (0.0, 0.0, 1.0), 5.0, 5.0),
+            ((0.0, 0.0, -5.0), (0.0, 0.0, 1.0), 4.0, 6.0),
+            ((0.5, 0.0, -5.0), (0.1, 1.0, 1.0), 6.80798, 7.08872),
+        ];
+        for ((ox, oy, oz), (dx, dy, dz), t0, t1) in cases {
+            let direction = Tuple::vector(dx, dy, dz).normal();
+            let r = Ray::new(Tuple::point(ox, oy, oz), direction);
+            let xs = cyl.local_intersect(&r);
+            assert_eq!(xs.len(), 2);
+            assert!((xs[0].t - t0).abs() < 0.0001);
+            assert!((xs[1].t - t1).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn normal_on_cylinder() {
+        let cyl = Cylinder::new();
+        let cases = [
+            ((1.0, 0.0, 0.0), (1.0, 0.0, 0.0)),
+            ((0.0, 5.0, -1.0), (0.0, 0.0, -1.0)),
+            ((0.0, -2.0, 1.0), (0.0, 0.0, 1.0)),
+            ((-1.0, 1.0, 0.0), (-1.0, 0.0, 0.0)),
+        ];
+        for ((px, py, pz), (nx, ny, nz)) in cases {
+            let n = cyl.local_normal_at(&Tuple::point(px, py, pz));
+            assert_eq!(n, Tuple::vector(nx, ny, nz));
+        }
+    }
+
+    #[test]
+    fn default_bounds() {
+        let cyl = Cylinder::new();
+        assert_eq!(cyl.minimum, f64::NEG_INFINITY);
+        assert_eq!(cyl.maximum, f64::INFINITY);
+        assert!(!cyl.closed);
+    }
+
+    #[test]
+    fn intersecting_constrained_cylinder() {
+        let cyl = Cylinder::truncated(1.0, 2.0, false);
+        let cases = [
+            ((0.0, 1.5, 0.0), (0.1, 1.0, 0.0), 0),
+            ((0.0, 3.0, -5.0), (0.0, 0.0, 1.0), 0),
+            ((0.0, 0.0, -5.0), (0.0, 0.0, 1.0), 0),
+            ((0.0, 2.0, -5.0), (0.0, 0.0, 1.0), 0),
+            ((0.0, 1.0, -5.0), (0.0, 0.0, 1.0), 0),
+            ((0.0, 1.5, -2.0), (0.0, 0.0, 1.0), 2),
+        ];
+        for ((ox, oy, oz), (dx, dy, dz), count) in cases {
+            let direction = Tuple::vector(dx, dy, dz).normal();
+            let r = Ray::new(Tuple::point(ox, oy, oz), direction);
+            assert_eq!(cyl.local_intersect(&r).len(), count);
+        }
+    }
+
+    #[test]
+    fn intersecting_caps() {
+        let cyl = Cylinder::truncated(1.0, 2.0, true);
+        let cases = [
+            ((0.0, 3.0, 0.0), (0.0, -1.0, 0.0), 2),
+            ((0.0, 3.0, -2.0), (0.0, -1.0, 2.0), 2),
+            ((0.0, 4.0, -2.0), (0.0, -1.0, 1.0), 2),
+            ((0.0, 0.0, -2.0), (0.0, 1.0, 2.0), 2),
+            ((0.0, -1.0, -2.0), (0.0, 1.0, 1.0), 2),
+        ];
+        for ((ox, oy, oz), (dx, dy, dz), count) in cases {
+            let direction = Tuple::vector(dx, dy, dz).normal();
+            let r = Ray::new(Tuple::point(ox, oy, oz), direction);
+            assert_eq!(cyl.local_intersect(&r).len(), count);
+        }
+    }
+
+    #[test]
+    fn normal_on_caps() {
+        let cyl = Cylinder::truncated(1.0, 2.0, true);
+        let cases = [
+            ((0.0, 1.0, 0.0), (0.0, -1.0, 0.0)),
+            ((0.5, 1.0, 0.0), (0.0, -1.0, 0.0)),
+            ((0.0, 1.0, 0.5), (0.0, -1.0, 0.0)),
+            ((0.0, 2.0, 0.0), (0.0, 1.0, 0.0)),
+            ((0.5, 2.0, 0.0), (0.0, 1.0, 0.0)),
+            ((0.0, 2.0, 0.5), (0.0, 1.0, 0.0)),
+        ];
+        for ((px, py, pz), (nx, ny, nz)) in cases {
+            let n = cyl.local_normal_at(&Tuple::point(px, py, pz));
+            assert_eq!(n, Tuple::vector(nx, ny, nz));
+        }
+    }
+
+    #[test]
+    fn bounds() {
+        let b = Cylinder::new().bounds();
+        assert_eq!(b.min.x(), -1.0);
+        assert_eq!(b.min.y(), f64::NEG_INFINITY);
+        assert_eq!(b.max.y(), f64::INFINITY);
+        let b = Cylinder::truncated(-5.0, 3.0, false).bounds();
+        assert_eq!(b.min, Tuple::point(-1.0, -5.0, -1.0));
+        assert_eq!(b.max, Tuple::point(1.0, 3.0, 1.0));
+    }
+}
@@ -0,0 +1,379 @@
+use crate::graphics::Material;
+use crate::math::{Matrix, Ray, Tuple};
+use crate::scene::Intersections;
+
+use super::bvh::Bvh;
+use super::{Aabb, Shape, ShapeData};
+
+/*
+ * A collection of child shapes transformed as one unit. The transform of a
+ * child is relative to the group. Rays that miss the combined bounds of the
+ * children skip testing them altogether, and once divided a bounding volume
+ * hierarchy narrows down which children need testing.
+ */
+#[derive(Debug, Default)]
+pub struct Group {
+    data: ShapeData,
+    children: Vec<Box<dyn Shape>>,
+    bounds: Aabb,
+    bvh: Option<Bvh>,
+}
+
+#[allow(dead_code)]
+impl Group {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn children(&self) -> &[Box<dyn Shape>] {
+        &self.children
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    pub fn add_child<S: Shape + 'static>(&mut self, child: S) {
+        self.add_boxed(Box::new(child));
+    }
+
+    pub fn add_boxed(&mut self, mut child: Box<dyn Shape>) {
+        child.insert_parents(0, &self.data.child_parents());
+        self.bounds.merge(&child.parent_space_bounds());
+        self.children.push(child);
+        /* The hierarchy no longer covers every child */
+        self.bvh = None;
+    }
+
+    pub fn with_child<S: Shape + 'static>(mut self, child: S) -> Self {
+        self.add_child(child);
+        self
+    }
+}
+
+impl Shape for Group {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        if !self.bounds.intersects(ray) {
+            return Intersections::empty();
+        }
+
+        let mut xs = vec![];
+        match &self.bvh {
+            Some(bvh) => {
+                for i in bvh.candidates(ray) {
+                    xs.extend(self.children[i].intersect(ray).iter());
+                }
+            }
+            None => {
+                for child in &self.children {
+                    xs.extend(child.intersect(ray).iter());
+                }
+            }
+        }
+        Intersections::new(xs)
+    }
+
+    fn local_normal_at(&self, _point: &Tuple) -> Tuple {
+        panic!("Groups have no surface, normals come from the child that was hit");
+    }
+
+    fn bounds(&self) -> Aabb {
+        self.bounds
+    }
+
+    /* Groups have no surface, so their material is handed to the children */
+    fn set_material(&mut self, material: Material) {
+        for child in self.children.iter_mut() {
+            child.set_material(material.clone());
+        }
+        self.data.set_material(material);
+    }
+
+    fn includes(&self, object: &dyn Shape) -> bool {
+        self.children.iter().any(|c| c.includes(object))
+    }
+
+    fn divide(&mut self, threshold: usize) {
+        for child in self.children.iter_mut() {
+            child.divide(threshold);
+        }
+        if self.children.len() > threshold {
+            let boxes: Vec<Aabb> = self
+                .children
+                .iter()
+                .map(|c| c.parent_space_bounds())
+                .collect();
+            self.bvh = Some(Bvh::build(&boxes, threshold));
+        }
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.data.set_transform(transform);
+        let depth = self.data.depth();
+        let inverse = self.data.inverse().clone();
+        for child in self.children.iter_mut() {
+            child.replace_parent(depth, &inverse);
+        }
+    }
+
+    fn insert_parents(&mut self, depth: usize, parents: &[Matrix]) {
+        self.data.insert_parents(depth, parents);
+        for child in self.children.iter_mut() {
+            child.insert_parents(depth, parents);
+        }
+    }
+
+    fn replace_parent(&mut self, depth: usize, inverse: &Matrix) {
+        self.data.replace_parent(depth, inverse);
+        for child in self.children.iter_mut() {
+            child.replace_parent(depth, inverse);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use crate::graphics::Material;
+    use crate::math::{Matrix, Ray, Tuple};
+    use crate::shapes::{Cylinder, Group, Shape, Sphere, TestShape};
+
+    #[test]
+    fn creating_group() {
+        let g = Group::new();
+        assert_eq!(g.transform(), &Matrix::identity());
+        assert!(g.is_empty());
+    }
+
+    #[test]
+    fn adding_child() {
+        let mut g = Group::new();
+        g.add_child(Sphere::new());
+        assert!(!g.is_empty());
+        assert_eq!(g.children().len(), 1);
+    }
+
+    #[test]
+    fn intersecting_empty_group() {
+        let g = Group::new();
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert!(g.local_intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn intersecting_nonempty_group() {
+        let mut g = Group::new();
+        g.add_child(Sphere::new());
+        g.add_child(Sphere::new().with_transform(Matrix::translation(0.0, 0.0, -3.0)));
+        g.add_child(Sphere::new().with_transform(Matrix::translation(5.0, 0.0, 0.0)));
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = g.local_intersect(&r);
+        let s1 = &*g.children()[0];
+        let s2 = &*g.children()[1];
+        assert_eq!(xs.len(), 4);
+        assert!(std::ptr::addr_eq(xs[0].object, s2));
+        assert!(std::ptr::addr_eq(xs[1].object, s2));
+        assert!(std::ptr::addr_eq(xs[2].object, s1));
+        assert!(std::ptr::addr_eq(xs[3].object, s1));
+    }
+
+    #[test]
+    fn intersecting_transformed_group() {
+        let mut g = Group::new().with_transform(Matrix::scaling(2.0, 2.0, 2.0));
+        g.add_child(Sphere::new().with_transform(Matrix::translation(5.0, 0.0, 0.0)));
+        let r = Ray::new(Tuple::point(10.0, 0.0, -10.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert_eq!(g.intersect(&r).len(), 2);
+    }
+
+    #[test]
+    fn world_to_object() {
+        let s = Sphere::new().with_transform(Matrix::translation(5.0, 0.0, 0.0));
+        let g2 = Group::new()
+            .with_transform(Matrix::scaling(2.0, 2.0, 2.0))
+            .with_child(s);
+        let g1 = Group::new()
+            .with_transform(Matrix::rotation_y(PI / 2.0))
+            .with_child(g2);
+        let r = Ray::new(Tuple::point(-5.0, 0.0, -10.0), Tuple::vector(1.0, 0.0, 0.0));
+        let xs = g1.intersect(&r);
+        let s = xs[0].object;
+        let p = s.world_to_object(&Tuple::point(-2.0, 0.0, -10.0));
+        assert_eq!(p.round(5), Tuple::point(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn normal_to_world() {
+        let s = Sphere::new().with_transform(Matrix::translation(5.0, 0.0, 0.0));
+        let g2 = Group::new()
+            .with_transform(Matrix::scaling(1.0, 2.0, 3.0))
+            .with_child(s);
+        let g1 = Group::new()
+            .with_transform(Matrix::rotation_y(PI / 2.0))
+            .with_child(g2);
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, -1.0));
+        let xs = g1.intersect(&r);
+        let s = xs[0].object;
+        let v = 3f64.sqrt() / 3.0;
+        let n = s.normal_to_world(&Tuple::vector(v, v, v));
+        assert_eq!(n.round(4), Tuple::vector(0.2857, 0.4286, -0.8571));
+    }
+
+    #[test]
+    fn normal_on_child() {
+        let s = Sphere::new().with_transform(Matrix::translation(5.0, 0.0, 0.0));
+        let g2 = Group::new()
+            .with_transform(Matrix::scaling(1.0, 2.0, 3.0))
+            .with_child(s);
+        let g1 = Group::new()
+            .with_transform(Matrix::rotation_y(PI / 2.0))
+            .with_child(g2);
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, -1.0));
+        let xs = g1.intersect(&r);
+        let s = xs[0].object;
+        let n = s.normal_at(&Tuple::point(1.7321, 1.1547, -5.5774));
+        assert_eq!(n.round(3), Tuple::vector(0.286, 0.429, -0.857));
+    }
+
+    #[test]
+    fn transforming_group_after_adding_children() {
+        let mut g = Group::new();
+        g.add_child(Sphere::new().with_transform(Matrix::translation(5.0, 0.0, 0.0)));
+        g.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+        let r = Ray::new(Tuple::point(10.0, 0.0, -10.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = g.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        let n = xs[0].object.normal_at(&Tuple::point(10.0, 0.0, -2.0));
+        assert_eq!(n.round(5), Tuple::vector(0.0, 0.0, -1.0));
+        let p = xs[0].object.world_to_object(&Tuple::point(10.0, 0.0, -2.0));
+        assert_eq!(p.round(5), Tuple::point(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn adding_group_to_group_keeps_chain() {
+        let mut inner = Group::new().with_transform(Matrix::translation(0.0, 3.0, 0.0));
+        inner.add_child(Sphere::new());
+        let mut outer = Group::new();
+        outer.add_child(inner);
+        outer.set_transform(Matrix::translation(4.0, 0.0, 0.0));
+        let r = Ray::new(Tuple::point(4.0, 3.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = outer.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(
+            xs[0].object.world_to_object(&Tuple::point(4.0, 3.0, -1.0)),
+            Tuple::point(0.0, 0.0, -1.0)
+        );
+    }
+
+    #[test]
+    fn group_bounds_contain_children() {
+        let s =
+            Sphere::new().with_transform(Matrix::scaling(2.0, 2.0, 2.0).translate(2.0, 5.0, -3.0));
+        let c = Cylinder::truncated(-2.0, 2.0, false)
+            .with_transform(Matrix::scaling(0.5, 1.0, 0.5).translate(-4.0, -1.0, 4.0));
+        let g = Group::new().with_child(s).with_child(c);
+        let b = g.bounds();
+        assert_eq!(b.min, Tuple::point(-4.5, -3.0, -5.0));
+        assert_eq!(b.max, Tuple::point(4.0, 7.0, 4.5));
+    }
+
+    #[test]
+    fn ray_misses_group_bounds() {
+        let child = TestShape::default();
+        let saved = child.saved_ray.clone();
+        let g = Group::new().with_child(child);
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 1.0, 0.0));
+        g.intersect(&r);
+        assert!(saved.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn ray_hits_group_bounds() {
+        let child = TestShape::default();
+        let saved = child.saved_ray.clone();
+        let g = Group::new().with_child(child);
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        g.intersect(&r);
+        assert!(saved.lock().unwrap().is_some());
+    }
+
+    fn row_of_spheres(count: usize) -> Group {
+        let mut g = Group::new();
+        for i in 0..count {
+            let x = i as f64 * 3.0;
+            g.add_child(Sphere::new().with_transform(Matrix::translation(x, 0.0, 0.0)));
+        }
+        g
+    }
+
+    #[test]
+    fn divided_group_finds_same_intersections() {
+        let plain = row_of_spheres(20);
+        let mut divided = row_of_spheres(20);
+        divided.divide(2);
+        for i in 0..20 {
+            let x = i as f64 * 3.0 + 0.5;
+            let r = Ray::new(Tuple::point(x, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+            let a: Vec<f64> = plain.intersect(&r).iter().map(|i| i.t).collect();
+            let b: Vec<f64> = divided.intersect(&r).iter().map(|i| i.t).collect();
+            assert_eq!(a.len(), 2);
+            assert_eq!(a, b);
+        }
+    }
+
+    #[test]
+    fn divided_group_skips_distant_children() {
+        let mut g = row_of_spheres(8);
+        let child = TestShape::default().with_transform(Matrix::translation(100.0, 0.0, 0.0));
+        let saved = child.saved_ray.clone();
+        g.add_child(child);
+        g.divide(1);
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert_eq!(g.intersect(&r).len(), 2);
+        assert!(saved.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn dividing_recurses_into_subgroups() {
+        let mut outer = Group::new();
+        let mut inner = row_of_spheres(6);
+        let child = TestShape::default().with_transform(Matrix::translation(100.0, 0.0, 0.0));
+        let saved = child.saved_ray.clone();
+        inner.add_child(child);
+        outer.add_child(inner);
+        outer.add_child(Sphere::new().with_transform(Matrix::translation(0.0, 10.0, 0.0)));
+        outer.divide(1);
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert_eq!(outer.intersect(&r).len(), 2);
+        assert!(saved.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn adding_child_discards_hierarchy() {
+        let mut g = row_of_spheres(4);
+        g.divide(1);
+        g.add_child(Sphere::new().with_transform(Matrix::translation(0.0, 0.0, 10.0)));
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert_eq!(g.intersect(&r).len(), 4);
+    }
+
+    #[test]
+    fn material_is_passed_to_children() {
+        let m = Material {
+            ambient: 1.0,
+            ..Default::default()
+        };
+        let mut g = Group::new().with_child(Group::new().with_child(Sphere::new()));
+        g.set_material(m.clone());
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert_eq!(g.intersect(&r)[0].object.material(), &m);
+    }
+}
@@ -0,0 +1,27 @@
+mod bounds;
+mod bvh;
+mod cone;
+mod csg;
+mod cube;
+mod cylinder;
+mod group;
+mod plane;
+mod shape;
+mod smooth_triangle;
+mod sphere;
+mod triangle;
+
+pub use self::bounds::Aabb;
+pub use self::bvh::BVH_THRESHOLD;
+pub use self::cone::Cone;
+pub use self::csg::{Csg, CsgOperation};
+pub use self::cube::Cube;
+pub use self::cylinder::Cylinder;
+pub use self::group::Group;
+pub use self::plane::Plane;
+#[cfg(test)]
+pub(crate) use self::shape::test::TestShape;
+pub use self::shape::{Shape, ShapeData};
+pub use self::smooth_triangle::SmoothTriangle;
+pub use self::sphere::Sphere;
+pub use self::triangle::Triangle;
@@ -0,0 +1,112 @@
+use crate::math::{Ray, Tuple, EPSILON};
+use crate::scene::{Intersection, Intersections};
+
+use super::{Aabb, Shape, ShapeData};
+
+/*
+ * An infinite plane spanning x and z, passing through the origin.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct Plane {
+    data: ShapeData,
+}
+
+#[allow(dead_code)]
+impl Plane {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Shape for Plane {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        if ray.direction.y().abs() < EPSILON {
+            /* Parallel or coplanar, either way nothing to see */
+            return Intersections::empty();
+        }
+
+        let t = -ray.origin.y() / ray.direction.y();
+        Intersections::new(vec![Intersection::new(t, self)])
+    }
+
+    fn local_normal_at(&self, _point: &Tuple) -> Tuple {
+        Tuple::vector(0.0, 1.0, 0.0)
+    }
+
+    fn bounds(&self) -> Aabb {
+        Aabb::new(
+            Tuple::point(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+            Tuple::point(f64::INFINITY, 0.0, f64::INFINITY),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::{Ray, Tuple};
+    use crate::shapes::{Plane, Shape};
+
+    #[test]
+    fn normal_is_constant() {
+        let p = Plane::new();
+        let n1 = p.local_normal_at(&Tuple::point(0.0, 0.0, 0.0));
+        let n2 = p.local_normal_at(&Tuple::point(10.0, 0.0, -10.0));
+        let n3 = p.local_normal_at(&Tuple::point(-5.0, 0.0, 150.0));
+        assert_eq!(n1, Tuple::vector(0.0, 1.0, 0.0));
+        assert_eq!(n2, Tuple::vector(0.0, 1.0, 0.0));
+        assert_eq!(n3, Tuple::vector(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn intersect_parallel_ray() {
+        let p = Plane::new();
+        let r = Ray::new(Tuple::point(0.0, 10.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert!(p.local_intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn intersect_coplanar_ray() {
+        let p = Plane::new();
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert!(p.local_intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn intersect_from_above() {
+        let p = Plane::new();
+        let r = Ray::new(Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+        let xs = p.local_intersect(&r);
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t, 1.0);
+        assert!(std::ptr::addr_eq(xs[0].object, &p));
+    }
+
+    #[test]
+    fn intersect_from_below() {
+        let p = Plane::new();
+        let r = Ray::new(Tuple::point(0.0, -1.0, 0.0), Tuple::vector(0.0, 1.0, 0.0));
+        let xs = p.local_intersect(&r);
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t, 1.0);
+        assert!(std::ptr::addr_eq(xs[0].object, &p));
+    }
+
+    #[test]
+    fn bounds() {
+        let b = Plane::new().bounds();
+        assert_eq!(b.min.x(), f64::NEG_INFINITY);
+        assert_eq!(b.min.y(), 0.0);
+        assert_eq!(b.min.z(), f64::NEG_INFINITY);
+        assert_eq!(b.max.x(), f64::INFINITY);
+        assert_eq!(b.max.y(), 0.0);
+        assert_eq!(b.max.z(), f64::INFINITY);
+    }
+}
@@ -0,0 +1,321 @@
+use std::fmt::Debug;
+
+use crate::graphics::Material;
+use crate::math::{Matrix, Ray, Tuple};
+use crate::scene::{Intersection, Intersections};
+
+use super::Aabb;
+
+/*
+ * State shared by every shape. Concrete shapes embed this and expose it
+ * through `Shape::data` so the conversions between world and object space are
+ * only implemented once.
+ */
+#[derive(Debug, Clone)]
+pub struct ShapeData {
+    transform: Matrix,
+    inverse: Matrix,
+    material: Material,
+    /* Inverse transforms of the enclosing groups, outermost first */
+    parents: Vec<Matrix>,
+}
+
+impl Default for ShapeData {
+    fn default() -> Self {
+        Self {
+            transform: Matrix::identity(),
+            inverse: Matrix::identity(),
+            material: Material::default(),
+            parents: vec![],
+        }
+    }
+}
+
+impl ShapeData {
+    pub fn set_transform(&mut self, transform: Matrix) {
+        /* The inverse is needed for every ray so compute it once */
+        self.inverse = transform.inverse();
+        self.transform = transform;
+    }
+
+    pub fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+
+    /* The parent chain a child of this shape would have */
+    pub fn child_parents(&self) -> Vec<Matrix> {
+        let mut parents = self.parents.clone();
+        parents.push(self.inverse.clone());
+        parents
+    }
+
+    pub fn depth(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn inverse(&self) -> &Matrix {
+        &self.inverse
+    }
+
+    pub fn insert_parents(&mut self, depth: usize, parents: &[Matrix]) {
+        self.parents.splice(depth..depth, parents.iter().cloned());
+    }
+
+    pub fn replace_parent(&mut self, depth: usize, inverse: &Matrix) {
+        self.parents[depth] = inverse.clone();
+    }
+}
+
+#[allow(dead_code)]
+pub trait Shape: Debug + Send + Sync {
+    fn data(&self) -> &ShapeData;
+
+    fn data_mut(&mut self) -> &mut ShapeData;
+
+    /* Intersect a ray that has already been converted to object space */
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_>;
+
+    /* Normal at a point given in object space */
+    fn local_normal_at(&self, point: &Tuple) -> Tuple;
+
+    /*
+     * Normal at an object space point for a specific hit. Shapes whose normal
+     * depends on more than the point, like smooth triangles, override this.
+     */
+    fn local_normal_at_hit(&self, point: &Tuple, _hit: &Intersection) -> Tuple {
+        self.local_normal_at(point)
+    }
+
+    /* Bounding box in object space */
+    fn bounds(&self) -> Aabb;
+
+    /* Bounding box in the space of the enclosing group */
+    fn parent_space_bounds(&self) -> Aabb {
+        self.bounds().transform(self.transform())
+    }
+
+    fn transform(&self) -> &Matrix {
+        &self.data().transform
+    }
+
+    fn inverse(&self) -> &Matrix {
+        &self.data().inverse
+    }
+
+    fn set_transform(&mut self, transform: Matrix) {
+        self.data_mut().set_transform(transform);
+    }
+
+    fn with_transform(mut self, transform: Matrix) -> Self
+    where
+        Self: Sized,
+    {
+        self.set_transform(transform);
+        self
+    }
+
+    fn material(&self) -> &Material {
+        &self.data().material
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.data_mut().set_material(material);
+    }
+
+    fn with_material(mut self, material: Material) -> Self
+    where
+        Self: Sized,
+    {
+        self.set_material(material);
+        self
+    }
+
+    fn intersect(&self, ray: &Ray) -> Intersections<'_> {
+        self.local_intersect(&ray.transform(self.inverse()))
+    }
+
+    fn normal_at(&self, world_point: &Tuple) -> Tuple {
+        let local_point = self.world_to_object(world_point);
+        let local_normal = self.local_normal_at(&local_point);
+        self.normal_to_world(&local_normal)
+    }
+
+    fn normal_at_hit(&self, world_point: &Tuple, hit: &Intersection) -> Tuple {
+        let local_point = self.world_to_object(world_point);
+        let local_normal = self.local_normal_at_hit(&local_point, hit);
+        self.normal_to_world(&local_normal)
+    }
+
+    /* Walk down the parent chain converting a world point to object space */
+    fn world_to_object(&self, point: &Tuple) -> Tuple {
+        let point = self
+            .data()
+            .parents
+            .iter()
+            .fold(*point, |point, inverse| inverse * point);
+        self.inverse() * point
+    }
+
+    /* Walk up the parent chain converting an object normal to world space */
+    fn normal_to_world(&self, normal: &Tuple) -> Tuple {
+        let to_parent = |inverse: &Matrix, normal: Tuple| {
+            let mut normal = inverse.transpose() * normal;
+            normal[3] = 0.0;
+            normal.normal()
+        };
+        self.data()
+            .parents
+            .iter()
+            .rev()
+            .fold(to_parent(self.inverse(), *normal), |normal, inverse| {
+                to_parent(inverse, normal)
+            })
+    }
+
+    /*
+     * Whether `object` is this shape or, for shapes with children, one of
+     * their descendants.
+     */
+    fn includes(&self, object: &dyn Shape) -> bool {
+        std::ptr::addr_eq(self, object)
+    }
+
+    /*
+     * Builds acceleration structures for any children, groups holding more
+     * than `threshold` children get split into a bounding volume hierarchy.
+     */
+    fn divide(&mut self, _threshold: usize) {}
+
+    /*
+     * Splices ancestor inverse transforms into the parent chain at `depth`.
+     * Shapes with children must pass this on to them.
+     */
+    fn insert_parents(&mut self, depth: usize, parents: &[Matrix]) {
+        self.data_mut().insert_parents(depth, parents);
+    }
+
+    /*
+     * Replaces the ancestor inverse transform at `depth`, used when an
+     * enclosing group is transformed after this shape was added to it.
+     */
+    fn replace_parent(&mut self, depth: usize, inverse: &Matrix) {
+        self.data_mut().replace_parent(depth, inverse);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
+    use std::sync::{Arc, Mutex};
+
+    use crate::graphics::Material;
+    use crate::math::{Matrix, Ray, Tuple};
+    use crate::scene::Intersections;
+    use crate::shapes::{Aabb, Shape, ShapeData};
+
+    /* Records the object space ray it was intersected with */
+    #[derive(Debug, Default)]
+    pub(crate) struct TestShape {
+        data: ShapeData,
+        /* Shared so tests can inspect it after the shape is moved */
+        pub saved_ray: Arc<Mutex<Option<Ray>>>,
+    }
+
+    impl Shape for TestShape {
+        fn data(&self) -> &ShapeData {
+            &self.data
+        }
+
+        fn data_mut(&mut self) -> &mut ShapeData {
+            &mut self.data
+        }
+
+        fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+            *self.saved_ray.lock().unwrap() = Some(*ray);
+            Intersections::empty()
+        }
+
+        fn local_normal_at(&self, point: &Tuple) -> Tuple {
+            Tuple::vector(point.x(), point.y(), point.z())
+        }
+
+        fn bounds(&self) -> Aabb {
+            Aabb::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0))
+        }
+    }
+
+    #[test]
+    fn default_transformation() {
+        let s = TestShape::default();
+        assert_eq!(s.transform(), &Matrix::identity());
+    }
+
+    #[test]
+    fn assigning_transformation() {
+        let s = TestShape::default().with_transform(Matrix::translation(2.0, 3.0, 4.0));
+        assert_eq!(s.transform(), &Matrix::translation(2.0, 3.0, 4.0));
+    }
+
+    #[test]
+    fn default_material() {
+        let s = TestShape::default();
+        assert_eq!(s.material(), &Material::default());
+    }
+
+    #[test]
+    fn assigning_material() {
+        let m = Material {
+            ambient: 1.0,
+            ..Default::default()
+        };
+        let s = TestShape::default().with_material(m.clone());
+        assert_eq!(s.material(), &m);
+    }
+
+    #[test]
+    fn intersecting_scaled_shape() {
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let s = TestShape::default().with_transform(Matrix::scaling(2.0, 2.0, 2.0));
+        s.intersect(&r);
+        let saved = s.saved_ray.lock().unwrap().unwrap();
+        assert_eq!(saved.origin, Tuple::point(0.0, 0.0, -2.5));
+        assert_eq!(saved.direction, Tuple::vector(0.0, 0.0, 0.5));
+    }
+
+    #[test]
+    fn intersecting_translated_shape() {
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let s = TestShape::default().with_transform(Matrix::translation(5.0, 0.0, 0.0));
+        s.intersect(&r);
+        let saved = s.saved_ray.lock().unwrap().unwrap();
+        assert_eq!(saved.origin, Tuple::point(-5.0, 0.0, -5.0));
+        assert_eq!(saved.direction, Tuple::vector(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn normal_on_translated_shape() {
+        let s = TestShape::default().with_transform(Matrix::translation(0.0, 1.0, 0.0));
+        let n = s.normal_at(&Tuple::point(0.0, 1.0 + FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+        assert_eq!(
+            n.round(5),
+            Tuple::vector(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2).round(5)
+        );
+    }
+
+    #[test]
+    fn normal_on_transformed_shape() {
+        let t = Matrix::identity().rotate_z(PI / 5.0).scale(1.0, 0.5, 1.0);
+        let s = TestShape::default().with_transform(t);
+        let n = s.normal_at(&Tuple::point(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+        assert_eq!(n.round(5), Tuple::vector(0.0, 0.97014, -0.24254));
+    }
+
+    #[test]
+    fn parent_space_bounds() {
+        let s = TestShape::default()
+            .with_transform(Matrix::scaling(0.5, 2.0, 4.0).translate(1.0, -3.0, 5.0));
+        let b = s.parent_space_bounds();
+        assert_eq!(b.min, Tuple::point(0.5, -5.0, 1.0));
+        assert_eq!(b.max, Tuple::point(1.5, -1.0, 9.0));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::math::{Ray, Tuple};
+use crate::scene::{Intersection, Intersections};
+
+use super::{Aabb, Shape, ShapeData, Triangle};
+
+/*
+ * A triangle with a normal for each of its points. The normal at a hit is
+ * interpolated from them using the u and v recorded on the intersection.
+ */
+#[derive(Debug, Clone)]
+pub struct SmoothTriangle {
+    flat: Triangle,
+    n1: Tuple,
+    n2: Tuple,
+    n3: Tuple,
+}
+
+#[allow(dead_code)]
+impl SmoothTriangle {
+    pub fn new(p1: Tuple, p2: Tuple, p3: Tuple, n1: Tuple, n2: Tuple, n3: Tuple) -> Self {
+        Self {
+            flat: Triangle::new(p1, p2, p3),
+            n1,
+            n2,
+            n3,
+        }
+    }
+
+    pub fn p1(&self) -> &Tuple {
+        self.flat.p1()
+    }
+
+    pub fn p2(&self) -> &Tuple {
+        self.flat.p2()
+    }
+
+    pub fn p3(&self) -> &Tuple {
+        self.flat.p3()
+    }
+
+    pub fn n1(&self) -> &Tuple {
+        &self.n1
+    }
+
+    pub fn n2(&self) -> &Tuple {
+        &self.n2
+    }
+
+    pub fn n3(&self) -> &Tuple {
+        &self.n3
+    }
+}
+
+impl Shape for SmoothTriangle {
+    fn data(&self) -> &ShapeData {
+        self.flat.data()
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        self.flat.data_mut()
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        match self.flat.hit(ray) {
+            Some((t, u, v)) => Intersections::new(vec![Intersection::with_uv(t, self, u, v)]),
+            None => Intersections::empty(),
+        }
+    }
+
+    fn local_normal_at(&self, point: &Tuple) -> Tuple {
+        self.flat.local_normal_at(point)
+    }
+
+    fn local_normal_at_hit(&self, _point: &Tuple, hit: &Intersection) -> Tuple {
+        self.n2 * hit.u + self.n3 * hit.v + self.n1 * (1.0 - hit.u - hit.v)
+    }
+
+    fn bounds(&self) -> Aabb {
+        self.flat.point_bounds()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::{round, Ray, Tuple};
+    use crate::scene::{Intersection, Intersections};
+    use crate::shapes::{Shape, SmoothTriangle};
+
+    fn triangle() -> SmoothTriangle {
+        SmoothTriangle::new(
+            Tuple::point(0.0, 1.0, 0.0),
+            Tuple::point(-1.0, 0.0, 0.0),
+            Tuple::point(1.0, 0.0, 0.0),
+            Tuple::vector(0.0, 1.0, 0.0),
+            Tuple::vector(-1.0, 0.0, 0.0),
+            Tuple::vector(1.0, 0.0, 0.0),
+        )
+    }
+
+    #[test]
+    fn constructing_smooth_triangle() {
+        let t = triangle();
+        assert_eq!(t.p1(), &Tuple::point(0.0, 1.0, 0.0));
+        assert_eq!(t.p2(), &Tuple::point(-1.0, 0.0, 0.0));
+        assert_eq!(t.p3(), &Tuple::point(1.0, 0.0, 0.0));
+        assert_eq!(t.n1(), &Tuple::vector(0.0, 1.0, 0.0));
+        assert_eq!(t.n2(), &Tuple::vector(-1.0, 0.0, 0.0));
+        assert_eq!(t.n3(), &Tuple::vector(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn intersection_stores_uv() {
+        let t = triangle();
+        let r = Ray::new(Tuple::point(-0.2, 0.3, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = t.local_intersect(&r);
+        assert_eq!(round(xs[0].u, 5), 0.45);
+        assert_eq!(round(xs[0].v, 5), 0.25);
+    }
+
+    #[test]
+    fn normal_is_interpolated() {
+        let t = triangle();
+        let i = Intersection::with_uv(1.0, &t, 0.45, 0.25);
+        let n = t.normal_at_hit(&Tuple::point(0.0, 0.0, 0.0), &i);
+        assert_eq!(n.round(5), Tuple::vector(-0.5547, 0.83205, 0.0));
+    }
+
+    #[test]
+    fn preparing_normal_on_smooth_triangle() {
+        let t = triangle();
+        let i = Intersection::with_uv(1.0, &t, 0.45, 0.25);
+        let r = Ray::new(Tuple::point(-0.2, 0.3, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+        let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+        assert_eq!(comps.normalv.round(5), Tuple::vector(-0.5547, 0.83205, 0.0));
+    }
+}
@@ -0,0 +1,206 @@
+use crate::math::{Ray, Tuple};
+use crate::scene::{Intersection, Intersections};
+
+use super::{Aabb, Shape, ShapeData};
+
+/*
+ * A unit sphere centered at the origin.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct Sphere {
+    data: ShapeData,
+}
+
+#[allow(dead_code)]
+impl Sphere {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(test)]
+impl Sphere {
+    pub fn glass() -> Self {
+        use crate::graphics::Material;
+
+        Self::new().with_material(Material {
+            transparency: 1.0,
+            refractive_index: 1.5,
+            ..Default::default()
+        })
+    }
+}
+
+impl Shape for Sphere {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        let sphere_to_ray = ray.origin - Tuple::point(0.0, 0.0, 0.0);
+
+        let a = ray.direction.dot(&ray.direction);
+        let b = 2.0 * ray.direction.dot(&sphere_to_ray);
+        let c = sphere_to_ray.dot(&sphere_to_ray) - 1.0;
+        let discriminant = b.powi(2) - 4.0 * a * c;
+
+        if discriminant < 0.0 {
+            return Intersections::empty();
+        }
+
+        let t1 = (-b - discriminant.sqrt()) / (2.0 * a);
+        let t2 = (-b + discriminant.sqrt()) / (2.0 * a);
+        Intersections::new(vec![
+            Intersection::new(t1, self),
+            Intersection::new(t2, self),
+        ])
+    }
+
+    fn local_normal_at(&self, point: &Tuple) -> Tuple {
+        *point - Tuple::point(0.0, 0.0, 0.0)
+    }
+
+    fn bounds(&self) -> Aabb {
+        Aabb::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+    use crate::math::{Matrix, Ray, Tuple};
+    use crate::shapes::{Shape, Sphere};
+
+    #[test]
+    fn ray_intersects_sphere() {
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let s = Sphere::new();
+        let xs = s.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 4.0);
+        assert_eq!(xs[1].t, 6.0);
+    }
+
+    #[test]
+    fn ray_intersects_sphere_at_tangent() {
+        let r = Ray::new(Tuple::point(0.0, 1.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let s = Sphere::new();
+        let xs = s.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 5.0);
+        assert_eq!(xs[1].t, 5.0);
+    }
+
+    #[test]
+    fn ray_misses_sphere() {
+        let r = Ray::new(Tuple::point(0.0, 2.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let s = Sphere::new();
+        assert!(s.intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn ray_originates_inside_sphere() {
+        let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+        let s = Sphere::new();
+        let xs = s.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, -1.0);
+        assert_eq!(xs[1].t, 1.0);
+    }
+
+    #[test]
+    fn sphere_behind_ray() {
+        let r = Ray::new(Tuple::point(0.0, 0.0, 5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let s = Sphere::new();
+        let xs = s.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, -6.0);
+        assert_eq!(xs[1].t, -4.0);
+    }
+
+    #[test]
+    fn intersect_sets_object() {
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let s = Sphere::new();
+        let xs = s.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert!(std::ptr::addr_eq(xs[0].object, &s));
+        assert!(std::ptr::addr_eq(xs[1].object, &s));
+    }
+
+    #[test]
+    fn intersecting_scaled_sphere() {
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let s = Sphere::new().with_transform(Matrix::scaling(2.0, 2.0, 2.0));
+        let xs = s.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 3.0);
+        assert_eq!(xs[1].t, 7.0);
+    }
+
+    #[test]
+    fn intersecting_translated_sphere() {
+        let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+        let s = Sphere::new().with_transform(Matrix::translation(5.0, 0.0, 0.0));
+        assert!(s.intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn normal_on_axes() {
+        let s = Sphere::new();
+        let n = s.normal_at(&Tuple::point(1.0, 0.0, 0.0));
+        assert_eq!(n, Tuple::vector(1.0, 0.0, 0.0));
+        let n = s.normal_at(&Tuple::point(0.0, 1.0, 0.0));
+        assert_eq!(n, Tuple::vector(0.0, 1.0, 0.0));
+        let n = s.normal_at(&Tuple::point(0.0, 0.0, 1.0));
+        assert_eq!(n, Tuple::vector(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn normal_at_nonaxial_point() {
+        let s = Sphere::new();
+        let v = 3f64.sqrt() / 3.0;
+        let n = s.normal_at(&Tuple::point(v, v, v));
+        assert_eq!(n.round(5), Tuple::vector(v, v, v).round(5));
+        assert_eq!(n.round(5), n.normal().round(5));
+    }
+
+    #[test]
+    fn normal_on_translated_sphere() {
+        let s = Sphere::new().with_transform(Matrix::translation(0.0, 1.0, 0.0));
+        let n = s.normal_at(&Tuple::point(0.0, 1.0 + FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+        assert_eq!(
+            n.round(5),
+            Tuple::vector(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2).round(5)
+        );
+    }
+
+    #[test]
+    fn normal_on_transformed_sphere() {
+        let t = Matrix::identity().rotate_z(PI / 5.0).scale(1.0, 0.5, 1.0);
+        let s = Sphere::new().with_transform(t);
+        let v = 2f64.sqrt() / 2.0;
+        let n = s.normal_at(&Tuple::point(0.0, v, -v));
+        assert_eq!(n.round(5), Tuple::vector(0.0, 0.97014, -0.24254));
+    }
+
+    #[test]
+    fn glassy_sphere() {
+        let s = Sphere::glass();
+        assert_eq!(s.transform(), &Matrix::identity());
+        assert_eq!(s.material().transparency, 1.0);
+        assert_eq!(s.material().refractive_index, 1.5);
+    }
+
+    #[test]
+    fn bounds() {
+        let s = Sphere::new();
+        assert_eq!(s.bounds().min, Tuple::point(-1.0, -1.0, -1.0));
+        assert_eq!(s.bounds().max, Tuple::point(1.0, 1.0, 1.0));
+    }
+}
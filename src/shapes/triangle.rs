@@ -0,0 +1,193 @@
+use crate::math::{Ray, Tuple, EPSILON};
+use crate::scene::{Intersection, Intersections};
+
+use super::{Aabb, Shape, ShapeData};
+
+/*
+ * A flat triangle. The edges and normal are computed once up front since
+ * meshes are made up of a great many of these.
+ */
+#[derive(Debug, Clone)]
+pub struct Triangle {
+    data: ShapeData,
+    p1: Tuple,
+    p2: Tuple,
+    p3: Tuple,
+    e1: Tuple,
+    e2: Tuple,
+    normal: Tuple,
+}
+
+#[allow(dead_code)]
+impl Triangle {
+    pub fn new(p1: Tuple, p2: Tuple, p3: Tuple) -> Self {
+        let e1 = p2 - p1;
+        let e2 = p3 - p1;
+        Self {
+            data: ShapeData::default(),
+            p1,
+            p2,
+            p3,
+            e1,
+            e2,
+            normal: e2.cross(&e1).normal(),
+        }
+    }
+
+    pub fn p1(&self) -> &Tuple {
+        &self.p1
+    }
+
+    pub fn p2(&self) -> &Tuple {
+        &self.p2
+    }
+
+    pub fn p3(&self) -> &Tuple {
+        &self.p3
+    }
+
+    /*
+     * Möller–Trumbore intersection, returning t along with the barycentric
+     * u and v of the hit.
+     */
+    pub(super) fn hit(&self, ray: &Ray) -> Option<(f64, f64, f64)> {
+        let dir_cross_e2 = ray.direction.cross(&self.e2);
+        let det = self.e1.dot(&dir_cross_e2);
+        if det.abs() < EPSILON {
+            /* The ray is parallel to the triangle */
+            return None;
+        }
+
+        let f = 1.0 / det;
+        let p1_to_origin = ray.origin - self.p1;
+        let u = f * p1_to_origin.dot(&dir_cross_e2);
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let origin_cross_e1 = p1_to_origin.cross(&self.e1);
+        let v = f * ray.direction.dot(&origin_cross_e1);
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        Some((f * self.e2.dot(&origin_cross_e1), u, v))
+    }
+
+    pub(super) fn point_bounds(&self) -> Aabb {
+        let mut bounds = Aabb::empty();
+        bounds.add_point(&self.p1);
+        bounds.add_point(&self.p2);
+        bounds.add_point(&self.p3);
+        bounds
+    }
+}
+
+impl Shape for Triangle {
+    fn data(&self) -> &ShapeData {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut ShapeData {
+        &mut self.data
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Intersections<'_> {
+        match self.hit(ray) {
+            Some((t, u, v)) => Intersections::new(vec![Intersection::with_uv(t, self, u, v)]),
+            None => Intersections::empty(),
+        }
+    }
+
+    fn local_normal_at(&self, _point: &Tuple) -> Tuple {
+        self.normal
+    }
+
+    fn bounds(&self) -> Aabb {
+        self.point_bounds()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::{Ray, Tuple};
+    use crate::shapes::{Shape, Triangle};
+
+    fn triangle() -> Triangle {
+        Triangle::new(
+            Tuple::point(0.0, 1.0, 0.0),
+            Tuple::point(-1.0, 0.0, 0.0),
+            Tuple::point(1.0, 0.0, 0.0),
+        )
+    }
+
+    #[test]
+    fn constructing_triangle() {
+        let t = triangle();
+        assert_eq!(t.p1(), &Tuple::point(0.0, 1.0, 0.0));
+        assert_eq!(t.p2(), &Tuple::point(-1.0, 0.0, 0.0));
+        assert_eq!(t.p3(), &Tuple::point(1.0, 0.0, 0.0));
+        assert_eq!(t.e1, Tuple::vector(-1.0, -1.0, 0.0));
+        assert_eq!(t.e2, Tuple::vector(1.0, -1.0, 0.0));
+        assert_eq!(t.normal, Tuple::vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn normal_of_triangle() {
+        let t = triangle();
+        let n1 = t.local_normal_at(&Tuple::point(0.0, 0.5, 0.0));
+        let n2 = t.local_normal_at(&Tuple::point(-0.5, 0.75, 0.0));
+        let n3 = t.local_normal_at(&Tuple::point(0.5, 0.25, 0.0));
+        assert_eq!(n1, t.normal);
+        assert_eq!(n2, t.normal);
+        assert_eq!(n3, t.normal);
+    }
+
+    #[test]
+    fn ray_parallel_to_triangle() {
+        let t = triangle();
+        let r = Ray::new(Tuple::point(0.0, -1.0, -2.0), Tuple::vector(0.0, 1.0, 0.0));
+        assert!(t.local_intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn ray_misses_p1_p3_edge() {
+        let t = triangle();
+        let r = Ray::new(Tuple::point(1.0, 1.0, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert!(t.local_intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn ray_misses_p1_p2_edge() {
+        let t = triangle();
+        let r = Ray::new(Tuple::point(-1.0, 1.0, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert!(t.local_intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn ray_misses_p2_p3_edge() {
+        let t = triangle();
+        let r = Ray::new(Tuple::point(0.0, -1.0, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+        assert!(t.local_intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn ray_strikes_triangle() {
+        let t = triangle();
+        let r = Ray::new(Tuple::point(0.0, 0.5, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+        let xs = t.local_intersect(&r);
+        assert_eq!(xs.len(), 1);
+        assert_eq!(xs[0].t, 2.0);
+    }
+
+    #[test]
+    fn bounds() {
+        let t = Triangle::new(
+            Tuple::point(-3.0, 7.0, 2.0),
+            Tuple::point(6.0, 2.0, -4.0),
+            Tuple::point(2.0, -1.0, -1.0),
+        );
+        assert_eq!(t.bounds().min, Tuple::point(-3.0, -1.0, -4.0));
+        assert_eq!(t.bounds().max, Tuple::point(6.0, 7.0, 2.0));
+    }
+}